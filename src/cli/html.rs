@@ -0,0 +1,200 @@
+use crate::cli::markdown::{channel_text, format_errors, properties};
+use crate::cli::printer::{convert_measurement, display_text, format_decay_value, format_measurement_value, format_unit, FootnoteMarkers};
+use crate::pdgdb::{DecayChannel, Particle};
+
+const STYLE: &str = "\
+body { font-family: system-ui, sans-serif; max-width: 60rem; margin: 2rem auto; padding: 0 1rem; color: #222; }
+nav ul { columns: 3; }
+table { border-collapse: collapse; margin: 0.5rem 0 1.5rem; width: 100%; }
+caption { text-align: left; font-weight: bold; padding: 0.25rem 0; }
+th, td { border-bottom: 1px solid #ddd; padding: 0.25rem 0.5rem; text-align: left; vertical-align: top; }
+th { background: #f4f4f4; }
+td.value { font-variant-numeric: tabular-nums; white-space: nowrap; }
+dl { display: grid; grid-template-columns: max-content auto; gap: 0.25rem 1rem; }
+dt { font-weight: bold; }
+dd { margin: 0; }";
+
+pub fn html_escape(text: &str) -> String{
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+// Anchors spell out the charge so tau- and tau+ differ, other symbols become "_"
+fn anchor(name: &str) -> String{
+    name.chars()
+        .map(|c| match c {
+            '+' => "plus".to_string(),
+            '-' => "minus".to_string(),
+            c if c.is_ascii_alphanumeric() => c.to_string(),
+            _ => "_".to_string(),
+        })
+        .collect()
+}
+
+fn table(caption: &str, header: &[&str], rows: &[Vec<String>], value_column: usize) -> String{
+    let mut lines = vec![
+        "<table>".to_string(),
+        format!("<caption>{}</caption>", html_escape(caption)),
+        format!("<thead><tr>{}</tr></thead>", header.iter().map(|title| format!("<th scope=\"col\">{}</th>", html_escape(title))).collect::<String>()),
+        "<tbody>".to_string(),
+    ];
+    for row in rows.iter() {
+        let cells = row
+            .iter()
+            .enumerate()
+            .map(|(i, text)| if i == value_column {
+                format!("<td class=\"value\">{}</td>", html_escape(text))
+            } else {
+                format!("<td>{}</td>", html_escape(text))
+            })
+            .collect::<String>();
+        lines.push(format!("<tr>{}</tr>", cells));
+    }
+    lines.push("</tbody>".to_string());
+    lines.push("</table>".to_string());
+    lines.join("\n")
+}
+
+// The footnotes of a table as a list below it, numbered like their markers
+fn footnote_list(markers: &FootnoteMarkers, particle: &Particle) -> Option<String>{
+    let notes = markers.notes(particle);
+    if notes.is_empty() {
+        return None;
+    }
+    let items = notes
+        .iter()
+        .map(|(number, text)| format!("<li value=\"{}\">{}</li>", number, html_escape(&display_text(text))))
+        .collect::<String>();
+    Some(format!("<ol class=\"footnotes\">{}</ol>", items))
+}
+
+// A `<section>` with anchors for the particle, its measurements and its decays
+pub fn html_particle(particle: &Particle) -> String{
+    let pdg_name = particle.name.as_deref().unwrap_or("Unknown");
+    let id = anchor(pdg_name);
+    let mut lines = vec![
+        format!("<section id=\"{}\">", id),
+        format!("<h2><a href=\"#{}\">{}</a></h2>", id, html_escape(&display_text(pdg_name))),
+        "<dl>".to_string(),
+    ];
+    for (label, value) in properties(particle) {
+        lines.push(format!("<dt>{}</dt><dd>{}</dd>", label, html_escape(&value)));
+    }
+    lines.push("</dl>".to_string());
+    if let Some(measurements) = &particle.measurements {
+        let mut markers = FootnoteMarkers::default();
+        let rows = measurements
+            .iter()
+            .map(convert_measurement)
+            .map(|measurement| vec![
+                markers.mark(display_text(measurement.description.as_deref().unwrap_or("Unknown")), &measurement.footnote_ids),
+                format_measurement_value(&measurement),
+                format_unit(&measurement.unit_text),
+            ])
+            .collect::<Vec<Vec<String>>>();
+        lines.push(format!("<h3 id=\"{}-measurements\">Measurements</h3>", id));
+        lines.push(table("Measurements", &["Description", "Value", "Unit"], &rows, 1));
+        lines.extend(footnote_list(&markers, particle));
+    }
+    if let Some(decays) = &particle.decay {
+        let mut markers = FootnoteMarkers::default();
+        let rows = decays
+            .iter()
+            .map(|decay| vec![
+                markers.mark(display_text(decay.description.as_deref().unwrap_or("Unknown")), &decay.footnote_ids),
+                format_decay_value(decay),
+                format_errors(decay.plus_error, decay.minus_error),
+            ])
+            .collect::<Vec<Vec<String>>>();
+        lines.push(format!("<h3 id=\"{}-decays\">Decays</h3>", id));
+        lines.push(table("Decays", &["Decay", "Value", "Errors"], &rows, 1));
+        lines.extend(footnote_list(&markers, particle));
+    }
+    let legend = particle.code_legend();
+    if !legend.is_empty() {
+        let rows = legend.into_iter().map(|(code, meaning)| vec![code, meaning]).collect::<Vec<Vec<String>>>();
+        lines.push(format!("<h3 id=\"{}-codes\">Codes</h3>", id));
+        lines.push(table("Codes", &["Code", "Meaning"], &rows, usize::MAX));
+    }
+    lines.push("</section>".to_string());
+    lines.join("\n")
+}
+
+pub fn html_decays(decay_channels: &[DecayChannel]) -> String{
+    let rows = decay_channels
+        .iter()
+        .map(|channel| vec![channel.pdgid.clone(), channel_text(channel)])
+        .collect::<Vec<Vec<String>>>();
+    html_document("Decays", &[format!("<section id=\"decays\">\n{}\n</section>", table("Related decays", &["Node", "Decay"], &rows, 0))])
+}
+
+// A standalone page with the CSS embedded
+pub fn html_document(title: &str, sections: &[String]) -> String{
+    let mut lines = vec![
+        "<!DOCTYPE html>".to_string(),
+        "<html lang=\"en\">".to_string(),
+        "<head>".to_string(),
+        "<meta charset=\"utf-8\">".to_string(),
+        format!("<title>{}</title>", html_escape(title)),
+        format!("<style>\n{}\n</style>", STYLE),
+        "</head>".to_string(),
+        "<body>".to_string(),
+        "<main>".to_string(),
+        format!("<h1>{}</h1>", html_escape(title)),
+    ];
+    lines.extend(sections.iter().cloned());
+    lines.push("</main>".to_string());
+    lines.push("<footer><p>Generated by pdgQuery from the PDG database.</p></footer>".to_string());
+    lines.push("</body>".to_string());
+    lines.push("</html>".to_string());
+    lines.join("\n")
+}
+
+// One page for a list of particles with a table of contents
+pub fn html_report(particles: &[Particle]) -> String{
+    let contents = particles
+        .iter()
+        .map(|particle| {
+            let name = particle.name.as_deref().unwrap_or("Unknown");
+            format!("<li><a href=\"#{}\">{}</a></li>", anchor(name), html_escape(&display_text(name)))
+        })
+        .collect::<Vec<String>>()
+        .join("\n");
+    let mut sections = vec![format!("<nav aria-label=\"Particles\">\n<ul>\n{}\n</ul>\n</nav>", contents)];
+    sections.extend(particles.iter().map(html_particle));
+    html_document("Particle report", &sections)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_anchor(){
+        assert_eq!(anchor("tau-"), "tauminus");
+        assert_eq!(anchor("tau+"), "tauplus");
+        assert_eq!(anchor("K*(892)bar0"), "K__892_bar0");
+    }
+
+    #[test]
+    fn test_html_report(){
+        let muon = Particle::test_muon();
+        let report = html_report(&[muon]);
+        assert!(report.starts_with("<!DOCTYPE html>"));
+        assert!(report.contains("<style>"));
+        assert!(report.contains("<li><a href=\"#muminus\">μ-</a></li>"));
+        assert!(report.contains("<section id=\"muminus\">"));
+        assert!(report.contains("<dt>PDG ID</dt><dd>13</dd>"));
+        assert!(report.ends_with("</html>"));
+    }
+
+    #[test]
+    fn test_table(){
+        let rows = vec![vec!["K- pi+".to_string(), "<1e-5".to_string()]];
+        let table = table("Decays", &["Decay", "Value"], &rows, 1);
+        assert!(table.contains("<th scope=\"col\">Decay</th>"));
+        assert!(table.contains("<tr><td>K- pi+</td><td class=\"value\">&lt;1e-5</td></tr>"));
+    }
+}
@@ -0,0 +1,144 @@
+// ASCII ideogram in the style of the PDG listings: every measurement is a Gaussian with its area proportional
+// to 1/σ, their sum is drawn as a curve above the error bar of each measurement and of the average.
+use crate::cli::rounding::RoundedValue;
+use crate::pdgdb::measured::{Kind, Measured};
+
+// Width of a measurement in standard deviations that is still inside the plot
+const PLOT_SIGMAS: f64 = 2.0;
+
+struct Axis{
+    low: f64,
+    high: f64,
+    width: usize,
+}
+
+impl Axis{
+    fn column(&self, x: f64) -> usize{
+        let column = ((x - self.low) / (self.high - self.low) * self.width as f64).floor();
+        (column.max(0.0) as usize).min(self.width - 1)
+    }
+
+    fn center(&self, column: usize) -> f64{
+        self.low + (column as f64 + 0.5) * (self.high - self.low) / self.width as f64
+    }
+
+    // The ends of the axis at the precision of one column
+    fn label(&self, x: f64) -> String{
+        let step = (self.high - self.low) / self.width as f64;
+        let rounded = RoundedValue::new(x, Some(step), Some(step));
+        let (value, _, _) = rounded.scaled();
+        match rounded.exponent() {
+            0 => value,
+            exponent => format!("{}e{}", value, exponent),
+        }
+    }
+}
+
+// An error bar from x - σ to x + σ with the value marked
+fn bar(axis: &Axis, measured: &Measured, ends: (char, char), line: char, mark: char) -> String{
+    let mut row = vec![' '; axis.width];
+    let (low, high) = (axis.column(measured.low()), axis.column(measured.high()));
+    for cell in row.iter_mut().take(high + 1).skip(low) {
+        *cell = line;
+    }
+    row[low] = ends.0;
+    row[high] = ends.1;
+    row[axis.column(measured.value)] = mark;
+    row.into_iter().collect::<String>().trim_end().to_string()
+}
+
+// Labelled measurements with the average below them, empty without two central values with errors to compare
+pub fn ideogram(measurements: &[(String, Measured)], average: Option<&Measured>, width: usize, height: usize) -> Vec<String>{
+    let measurements = measurements
+        .iter()
+        .filter(|(_, measured)| measured.kind == Kind::Central && measured.error().is_some_and(|error| error > 0.0))
+        .collect::<Vec<&(String, Measured)>>();
+    if measurements.len() < 2 || width < 2 || height == 0 {
+        return Vec::new();
+    }
+    let ends = measurements
+        .iter()
+        .map(|(_, measured)| measured)
+        .chain(average)
+        .flat_map(|measured| {
+            // an average with unknown errors only adds its value
+            let error = measured.error().unwrap_or_default();
+            [measured.value - PLOT_SIGMAS * error, measured.value + PLOT_SIGMAS * error]
+        });
+    let (low, high) = ends.fold((f64::INFINITY, f64::NEG_INFINITY), |(low, high), x| (low.min(x), high.max(x)));
+    let axis = Axis{ low, high, width };
+
+    let curve = (0..width)
+        .map(|column| {
+            let x = axis.center(column);
+            measurements
+                .iter()
+                .map(|(_, measured)| {
+                    let sigma = measured.error().unwrap_or_default();
+                    (-(x - measured.value).powi(2) / (2.0 * sigma * sigma)).exp() / (sigma * sigma)
+                })
+                .sum::<f64>()
+        })
+        .collect::<Vec<f64>>();
+    let peak = curve.iter().cloned().fold(0.0, f64::max);
+
+    let label_width = measurements.iter().map(|(label, _)| label.chars().count()).max().unwrap_or(0).max("Average".len());
+    let pad = " ".repeat(label_width + 1);
+    let mut lines = (1..=height)
+        .rev()
+        .map(|row| {
+            let line = curve
+                .iter()
+                .map(|level| {
+                    // equal peaks stay equal whatever the rounding
+                    let level = level / peak * height as f64 + 1e-9;
+                    if level >= row as f64 { '#' } else if level >= row as f64 - 0.5 { '.' } else { ' ' }
+                })
+                .collect::<String>();
+            format!("{}{}", pad, line).trim_end().to_string()
+        })
+        .collect::<Vec<String>>();
+    lines.push(format!("{}{}", pad, "-".repeat(width)));
+    let (low_label, high_label) = (axis.label(low), axis.label(high));
+    let gap = width.saturating_sub(low_label.len() + high_label.len()).max(1);
+    lines.push(format!("{}{}{}{}", pad, low_label, " ".repeat(gap), high_label));
+    for (label, measured) in &measurements {
+        lines.push(format!("{:<width$} {}", label, bar(&axis, measured, ('|', '|'), '-', 'o'), width = label_width));
+    }
+    if let Some(average) = average.filter(|average| average.kind == Kind::Central) {
+        lines.push(format!("{:<width$} {}", "Average", bar(&axis, average, ('[', ']'), '=', '*'), width = label_width));
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ideogram(){
+        let measurements = [
+            ("A 2001".to_string(), Measured::new(10.0, 1.0, 1.0)),
+            ("B 2002".to_string(), Measured::new(14.0, 1.0, 1.0)),
+        ];
+        let lines = ideogram(&measurements, Some(&Measured::new(12.0, 0.7, 0.7)), 24, 3);
+        // three rows of the curve, the axis with its labels and three error bars
+        assert_eq!(lines.len(), 8);
+        assert_eq!(lines[3], format!("{}{}", " ".repeat(8), "-".repeat(24)));
+        assert_eq!(lines[4], "        8.00               16.00");
+        assert_eq!(lines[5], "A 2001     |--o--|");
+        assert_eq!(lines[6], "B 2002                 |--o--|");
+        assert_eq!(lines[7], "Average          [==*=]");
+        // two separated measurements give two equal peaks
+        assert_eq!(lines[1], "          .######.    .######.");
+    }
+
+    #[test]
+    fn test_ideogram_needs_two_values(){
+        let measurements = [
+            ("A 2001".to_string(), Measured::new(10.0, 1.0, 1.0)),
+            ("B 2002".to_string(), Measured::upper_limit(14.0, None)),
+        ];
+        assert!(ideogram(&measurements, None, 24, 3).is_empty());
+    }
+}
@@ -0,0 +1,217 @@
+use crate::cli::particleName::ParticleName;
+use crate::cli::printer::{convert_measurement, FootnoteMarkers};
+use crate::cli::rounding::RoundedValue;
+use crate::pdgdb::naming::{name_table, Convention};
+use crate::pdgdb::{DecayChannel, Particle, ParticleDecay, ParticleMeasurement};
+use crate::pdgdb::measured::{Kind, Measured};
+
+pub fn latex_escape(text: &str) -> String{
+    text.chars()
+        .map(|c| match c {
+            '\\' => "\\textbackslash{}".to_string(),
+            '&' | '%' | '$' | '#' | '_' | '{' | '}' => format!("\\{}", c),
+            '~' => "\\textasciitilde{}".to_string(),
+            '^' => "\\textasciicircum{}".to_string(),
+            c => c.to_string(),
+        })
+        .collect()
+}
+
+// Math mode symbol of a PDG name without the dollars, the name table first, then the name grammar
+pub fn latex_name(name: &str) -> Option<String>{
+    if let Some(latex) = name_table().translate(name, Convention::Pdg, Convention::Latex) {
+        return Some(latex.to_string());
+    }
+    ParticleName::parse(name).map(|parsed| parsed.to_latex())
+}
+
+// Descriptions mix names with words, i.e., "tau- --> mu- gamma" or "tau MEAN LIFE", consecutive words share one \text
+pub fn latex_description(description: &str) -> String{
+    let mut tokens: Vec<String> = Vec::new();
+    let mut words: Vec<&str> = Vec::new();
+    let flush = |tokens: &mut Vec<String>, words: &mut Vec<&str>| {
+        if !words.is_empty() {
+            tokens.push(format!("\\text{{{}}}", latex_escape(&words.join(" "))));
+            words.clear();
+        }
+    };
+    for token in description.split_whitespace() {
+        let symbol = match token {
+            "-->" | "->" => Some("\\to".to_string()),
+            _ => latex_name(token),
+        };
+        match symbol {
+            Some(symbol) => {
+                flush(&mut tokens, &mut words);
+                tokens.push(symbol);
+            },
+            None => words.push(token),
+        }
+    }
+    flush(&mut tokens, &mut words);
+    format!("${}$", tokens.join(" "))
+}
+
+pub fn latex_channel(channel: &DecayChannel) -> String{
+    let mut daughters = channel.daughters.iter().collect::<Vec<(&String, &u16)>>();
+    daughters.sort();
+    let daughters = daughters
+        .iter()
+        .map(|(name, multiplicity)| {
+            let symbol = latex_name(name).unwrap_or(format!("\\text{{{}}}", latex_escape(name)));
+            if **multiplicity > 1 { format!("{}{}", multiplicity, symbol) } else { symbol }
+        })
+        .collect::<Vec<String>>();
+    let parent = latex_name(&channel.parent).unwrap_or(format!("\\text{{{}}}", latex_escape(&channel.parent)));
+    format!("${} \\to {}$", parent, daughters.join(" "))
+}
+
+// PDG units in text mode, i.e., "GeV**2" gives "GeV$^{2}$"
+pub fn latex_unit(unit: &str) -> String{
+    match unit.split_once("**") {
+        Some((base, power)) => format!("{}$^{{{}}}$", latex_escape(base), power),
+        None => latex_escape(unit),
+    }
+}
+
+// The value in math mode with its errors, limits and ranges, PDG rounded and scaled by a power of ten when it is far from one
+pub fn latex_value(measured: Option<&Measured>) -> String{
+    let measured = match measured {
+        Some(measured) => measured,
+        None => return "--".to_string(),
+    };
+    let rounded = RoundedValue::new(measured.value, measured.plus_error, measured.minus_error);
+    let times = match rounded.exponent() {
+        0 => String::new(),
+        exponent => format!(" \\times 10^{{{}}}", exponent),
+    };
+    match (measured.kind, rounded.scaled()) {
+        (Kind::UpperLimit{ .. }, (value, _, _)) => format!("$< {}{}$", value, times),
+        (Kind::LowerLimit{ .. }, (value, _, _)) => format!("$> {}{}$", value, times),
+        (Kind::Range, _) => {
+            let (low, high) = rounded.range();
+            format!("${}$ -- ${}$", low, high)
+        },
+        (_, (value, Some(plus), Some(_))) if rounded.is_symmetric() && times.is_empty() => format!("${} \\pm {}$", value, plus),
+        (_, (value, Some(plus), Some(_))) if rounded.is_symmetric() => format!("$({} \\pm {}){}$", value, plus, times),
+        (_, (value, Some(plus), Some(minus))) => format!("${}^{{+{}}}_{{-{}}}{}$", value, plus, minus, times),
+        (_, (value, _, _)) => format!("${}{}$", value, times),
+    }
+}
+
+fn tabular(columns: &str, header: &[&str], rows: &[Vec<String>]) -> String{
+    let mut lines = vec![
+        format!("\\begin{{tabular}}{{{}}}", columns),
+        "\\hline".to_string(),
+        format!("{} \\\\", header.join(" & ")),
+        "\\hline".to_string(),
+    ];
+    lines.extend(rows.iter().map(|row| format!("{} \\\\", row.join(" & "))));
+    lines.push("\\hline".to_string());
+    lines.push("\\end{tabular}".to_string());
+    lines.join("\n")
+}
+
+// The footnotes of a table as paragraphs below it, i.e., "[1] From a fit."
+fn footnote_paragraphs(markers: &FootnoteMarkers, particle: &Particle) -> Option<String>{
+    let notes = markers.notes(particle);
+    if notes.is_empty() {
+        return None;
+    }
+    Some(notes.iter().map(|(number, text)| format!("[{}] {}", number, latex_escape(text))).collect::<Vec<String>>().join("\n\n"))
+}
+
+fn decay_row(decay: &ParticleDecay, markers: &mut FootnoteMarkers) -> Vec<String>{
+    vec![
+        markers.mark(latex_description(decay.description.as_deref().unwrap_or("")), &decay.footnote_ids),
+        latex_value(Measured::from_decay(decay).as_ref()),
+    ]
+}
+
+fn measurement_row(measurement: &ParticleMeasurement, markers: &mut FootnoteMarkers) -> Vec<String>{
+    let measurement = &convert_measurement(measurement);
+    vec![
+        markers.mark(latex_description(measurement.description.as_deref().unwrap_or("")), &measurement.footnote_ids),
+        latex_value(Measured::from_measurement(measurement).as_ref()),
+        latex_unit(measurement.unit_text.as_deref().unwrap_or("")),
+    ]
+}
+
+pub fn latex_particle(particle: &Particle) -> String{
+    let name = particle.name.as_deref().unwrap_or("Unknown");
+    let mut blocks = vec![format!(
+        "% {} from the PDG database, generated by pdgQuery\n% ${}$",
+        name,
+        latex_name(name).unwrap_or(latex_escape(name)),
+    )];
+    if let Some(measurements) = &particle.measurements {
+        let mut markers = FootnoteMarkers::default();
+        let rows = measurements.iter().map(|measurement| measurement_row(measurement, &mut markers)).collect::<Vec<Vec<String>>>();
+        blocks.push(tabular("lll", &["Quantity", "Value", "Unit"], &rows));
+        blocks.extend(footnote_paragraphs(&markers, particle));
+    }
+    if let Some(decays) = &particle.decay {
+        let mut markers = FootnoteMarkers::default();
+        let rows = decays.iter().map(|decay| decay_row(decay, &mut markers)).collect::<Vec<Vec<String>>>();
+        blocks.push(tabular("ll", &["Mode", "Fraction $\\Gamma_i/\\Gamma$"], &rows));
+        blocks.extend(footnote_paragraphs(&markers, particle));
+    }
+    let legend = particle.code_legend();
+    if !legend.is_empty() {
+        let rows = legend
+            .iter()
+            .map(|(code, meaning)| vec![latex_escape(code), latex_escape(meaning)])
+            .collect::<Vec<Vec<String>>>();
+        blocks.push(tabular("ll", &["Code", "Meaning"], &rows));
+    }
+    blocks.join("\n\n")
+}
+
+pub fn latex_decays(decay_channels: &[DecayChannel]) -> String{
+    let rows = decay_channels
+        .iter()
+        .map(|channel| vec![latex_escape(&channel.pdgid), latex_channel(channel)])
+        .collect::<Vec<Vec<String>>>();
+    tabular("ll", &["Node", "Decay"], &rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_latex_value(){
+        assert_eq!(latex_value(Some(&Measured::new(1.77686, 0.00012, 0.00012))), "$1.77686 \\pm 0.00012$");
+        assert_eq!(latex_value(Some(&Measured::new(0.0389, 0.0004, 0.0003))), "$0.03890^{+0.00040}_{-0.00030}$");
+        assert_eq!(latex_value(Some(&Measured::upper_limit(4.2e-13, Some(0.9)))), "$< 4.2 \\times 10^{-13}$");
+        assert_eq!(latex_value(Some(&Measured::lower_limit(2.0e-3, None))), "$> 2 \\times 10^{-3}$");
+        assert_eq!(latex_value(Some(&Measured::new(2.1969811e-6, 2.2e-12, 2.2e-12))), "$(2.1969811 \\pm 0.0000022) \\times 10^{-6}$");
+        assert_eq!(latex_value(Some(&Measured::new(0.827, 0.367, 0.367))), "$0.8 \\pm 0.4$");
+        assert_eq!(latex_value(Some(&Measured::range(0.5, 1.25))), "$0.5$ -- $1.3$");
+        assert_eq!(latex_value(None), "--");
+    }
+
+    #[test]
+    fn test_latex_description(){
+        assert_eq!(latex_description("B(s)0 --> J/psi(1S) phi(1020)"), "$B_{s}^{0} \\to J/\\psi(1S) \\phi(1020)$");
+        assert_eq!(latex_description("tau MEAN LIFE"), "$\\tau \\text{MEAN LIFE}$");
+        assert_eq!(latex_description("D0 --> K- anything of total"), "$D^{0} \\to K^{-} \\text{anything of total}$");
+        assert_eq!(latex_unit("GeV**2"), "GeV$^{2}$");
+        assert_eq!(latex_escape("a_b & 5%"), "a\\_b \\& 5\\%");
+    }
+
+    #[test]
+    fn test_latex_decays(){
+        let channel = DecayChannel{
+            parent: "D0".to_string(),
+            daughters: HashMap::from([("K-".to_string(), 1), ("pi+".to_string(), 1), ("pi0".to_string(), 2)]),
+            pdgid: "S032.12".to_string(),
+        };
+        assert_eq!(latex_channel(&channel), "$D^{0} \\to K^{-} \\pi^{+} 2\\pi^{0}$");
+        let table = latex_decays(&[channel]);
+        assert!(table.starts_with("\\begin{tabular}{ll}\n\\hline\nNode & Decay \\\\"));
+        assert!(table.contains("S032.12 & $D^{0}"));
+        assert!(table.ends_with("\\end{tabular}"));
+    }
+}
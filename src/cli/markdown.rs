@@ -0,0 +1,177 @@
+use crate::cli::printer::{convert_measurement, display_text, format_decay_value, format_measurement_value, format_unit, FootnoteMarkers};
+use crate::pdgdb::{DecayChannel, Particle};
+
+// Pipes would end a GFM table cell
+fn cell(text: &str) -> String{
+    text.replace('\\', "\\\\").replace('|', "\\|").replace('\n', " ")
+}
+
+// The footnotes of a table as a list below it, i.e., "- [1] From a fit."
+fn footnote_list(markers: &FootnoteMarkers, particle: &Particle) -> Option<String>{
+    let notes = markers.notes(particle);
+    if notes.is_empty() {
+        return None;
+    }
+    Some(notes.iter().map(|(number, text)| format!("- [{}] {}", number, display_text(text))).collect::<Vec<String>>().join("\n"))
+}
+
+fn table(header: &[&str], rows: &[Vec<String>]) -> String{
+    let mut lines = vec![
+        format!("| {} |", header.join(" | ")),
+        format!("|{}", "---|".repeat(header.len())),
+    ];
+    lines.extend(rows.iter().map(|row| format!("| {} |", row.iter().map(|text| cell(text)).collect::<Vec<String>>().join(" | "))));
+    lines.join("\n")
+}
+
+pub(crate) fn format_errors(plus_error: Option<f64>, minus_error: Option<f64>) -> String{
+    match (plus_error.filter(|e| *e != 0.0), minus_error.filter(|e| *e != 0.0)) {
+        (Some(plus), Some(minus)) => format!("+{:.2e} -{:.2e}", plus, minus),
+        _ => String::new(),
+    }
+}
+
+// Name, MC ID and quantum numbers as (label, value) pairs, shared with the HTML report
+pub(crate) fn properties(particle: &Particle) -> Vec<(&'static str, String)>{
+    let unknown = |value: &Option<String>| value.clone().unwrap_or("Unknown".to_string());
+    vec![
+        ("Name", display_text(&unknown(&particle.name))),
+        ("PDG ID", particle.pdgid.map_or("Unknown".to_string(), |id| id.to_string())),
+        ("Node ID", unknown(&particle.node_id)),
+        ("Charge", particle.charge.map_or("Unknown".to_string(), |charge| charge.to_string())),
+        ("J Spin", unknown(&particle.j_spin)),
+        ("I Spin", unknown(&particle.i_spin)),
+        ("Charge Parity", unknown(&particle.charge_parity)),
+        ("Space Parity", unknown(&particle.space_parity)),
+        ("G Parity", unknown(&particle.g_parity)),
+    ]
+}
+
+pub fn markdown_particle(particle: &Particle) -> String{
+    let name = display_text(particle.name.as_deref().unwrap_or("Unknown"));
+    let rows = properties(particle)
+        .into_iter()
+        .map(|(label, value)| vec![label.to_string(), value])
+        .collect::<Vec<Vec<String>>>();
+    let mut blocks = vec![format!("## {}", name), table(&["Property", "Value"], &rows)];
+    if let Some(measurements) = &particle.measurements {
+        let mut markers = FootnoteMarkers::default();
+        let rows = measurements
+            .iter()
+            .map(convert_measurement)
+            .map(|measurement| vec![
+                markers.mark(display_text(measurement.description.as_deref().unwrap_or("Unknown")), &measurement.footnote_ids),
+                format_measurement_value(&measurement),
+                format_unit(&measurement.unit_text),
+            ])
+            .collect::<Vec<Vec<String>>>();
+        blocks.push("### Measurements".to_string());
+        blocks.push(table(&["Description", "Value", "Unit"], &rows));
+        blocks.extend(footnote_list(&markers, particle));
+    }
+    if let Some(decays) = &particle.decay {
+        let mut markers = FootnoteMarkers::default();
+        let rows = decays
+            .iter()
+            .map(|decay| vec![
+                markers.mark(display_text(decay.description.as_deref().unwrap_or("Unknown")), &decay.footnote_ids),
+                format_decay_value(decay),
+                format_errors(decay.plus_error, decay.minus_error),
+            ])
+            .collect::<Vec<Vec<String>>>();
+        blocks.push("### Decays".to_string());
+        blocks.push(table(&["Decay", "Value", "Errors"], &rows));
+        blocks.extend(footnote_list(&markers, particle));
+    }
+    let legend = particle.code_legend();
+    if !legend.is_empty() {
+        let rows = legend.into_iter().map(|(code, meaning)| vec![code, meaning]).collect::<Vec<Vec<String>>>();
+        blocks.push("### Codes".to_string());
+        blocks.push(table(&["Code", "Meaning"], &rows));
+    }
+    blocks.join("\n\n")
+}
+
+pub(crate) fn channel_text(channel: &DecayChannel) -> String{
+    let mut daughters = channel.daughters.iter().collect::<Vec<(&String, &u16)>>();
+    daughters.sort();
+    let daughters = daughters
+        .iter()
+        .map(|(name, multiplicity)| if **multiplicity > 1 { format!("{}{}", multiplicity, name) } else { name.to_string() })
+        .collect::<Vec<String>>();
+    display_text(&format!("{} -> {}", channel.parent, daughters.join(" + ")))
+}
+
+pub fn markdown_decays(decay_channels: &[DecayChannel]) -> String{
+    let rows = decay_channels
+        .iter()
+        .map(|channel| vec![channel.pdgid.clone(), channel_text(channel)])
+        .collect::<Vec<Vec<String>>>();
+    table(&["Node", "Decay"], &rows)
+}
+
+// One document for a list of particles
+pub fn markdown_report(particles: &[Particle]) -> String{
+    let mut blocks = vec!["# Particle report".to_string(), "Generated by pdgQuery from the PDG database.".to_string()];
+    blocks.extend(particles.iter().map(markdown_particle));
+    blocks.join("\n\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdgdb::ParticleDecay;
+    use crate::pdgdb::pdgDoc::PdgDoc;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_table(){
+        let rows = vec![vec!["a|b".to_string(), "1".to_string()]];
+        assert_eq!(table(&["Name", "Value"], &rows), "| Name | Value |\n|---|---|\n| a\\|b | 1 |");
+    }
+
+    #[test]
+    fn test_markdown_particle(){
+        let muon = Particle::test_muon();
+        let markdown = markdown_particle(&muon);
+        assert!(markdown.starts_with("## μ-\n\n| Property | Value |"));
+        assert!(markdown.contains("| PDG ID | 13 |"));
+        assert!(!markdown.contains("### Codes"));
+    }
+
+    #[test]
+    fn test_markdown_codes(){
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE pdgdoc (id INTEGER, table_name TEXT, column_name TEXT, value TEXT, indicator TEXT, description TEXT, comment TEXT);
+             INSERT INTO pdgdoc VALUES (1, 'PDGDATA', 'LIMIT_TYPE', 'U', NULL, 'Upper limit', NULL);",
+        ).unwrap();
+        let mut muon = Particle::test_muon();
+        muon.doc = std::sync::Arc::new(PdgDoc::load(&conn));
+        muon.decay = Some(vec![ParticleDecay::test("S004.2", 4.2e-13, Some("U"))]);
+        assert!(markdown_particle(&muon).ends_with("### Codes\n\n| Code | Meaning |\n|---|---|\n| U | Upper limit |"));
+    }
+
+    #[test]
+    fn test_markdown_footnotes(){
+        use crate::pdgdb::footnotes::Footnote;
+        let mut muon = Particle::test_muon();
+        muon.footnotes = vec![Footnote{ id: 7, node_id: "S004".to_string(), index: None, text: "From a fit.".to_string() }];
+        muon.decay = Some(vec![ParticleDecay{ footnote_ids: vec![7], ..ParticleDecay::test("S004.2", 4.2e-13, None) }]);
+        let markdown = markdown_particle(&muon);
+        assert!(markdown.contains(" [1] |"));
+        assert!(markdown.ends_with("\n\n- [1] From a fit."));
+    }
+
+    #[test]
+    fn test_markdown_decays(){
+        let channel = DecayChannel{
+            parent: "D0".to_string(),
+            daughters: HashMap::from([("K-".to_string(), 1), ("pi+".to_string(), 1)]),
+            pdgid: "S032.12".to_string(),
+        };
+        assert_eq!(markdown_decays(std::slice::from_ref(&channel)), "| Node | Decay |\n|---|---|\n| S032.12 | D0 -> K- + π+ |");
+        let channel = DecayChannel{ daughters: HashMap::from([("K-".to_string(), 1), ("pi0".to_string(), 2)]), ..channel };
+        assert_eq!(channel_text(&channel), "D0 -> K- + 2π0");
+    }
+}
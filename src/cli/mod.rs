@@ -1,3 +1,10 @@
 pub mod printer;
 pub mod parser;
-pub mod printAlias;
\ No newline at end of file
+pub mod printAlias;
+pub mod particleName;
+pub mod latex;
+pub mod markdown;
+pub mod html;
+pub mod table;
+pub mod rounding;
+pub mod ideogram;
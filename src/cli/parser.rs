@@ -1,4 +1,11 @@
 use crate::pdgdb::queries::singleQueries::ParticleQuery;
+use crate::pdgdb::queries::decayTree::DecayTreeConfig;
+use crate::generator::eventWriter::EventFormat;
+use crate::interop::ExportFormat;
+use crate::interop::citation::CitationFormat;
+use crate::pdgdb::naming::Convention;
+use crate::pdgdb::quantity::Unit;
+use crate::cli::printAlias::OutputProfile;
 
 #[derive(PartialEq, Debug)]
 pub enum QueryType{
@@ -9,6 +16,14 @@ pub enum QueryType{
     ParentlessDecayPartial, // Query for decays with no parent specified, e.g., `pdgQuery ? -> e ? ?`
     DecayWildcard,     // Query for decays with wildcard matching, e.g., `pdgQuery mu -> e nu_e ?*`
     ParentlessDecayWildcard,     // Query for decays with wildcard matching, e.g., `pdgQuery ? -> e nu_e ?*`
+    DecayTree,             // Expand decays recursively down to stable particles, e.g., `pdgQuery tree D*(2010)+ --depth 2`
+    DecaySample,           // Sample decays by branching fraction as JSON lines, e.g., `pdgQuery sample tau- --events 100 --seed 1`
+    Export,                // Write decay tables for generators, e.g., `pdgQuery export --evtgen D0 D+`
+    ConservationCheck,     // Check conservation laws of any decay, e.g., `pdgQuery check "tau- -> mu- gamma"`
+    Report,                // A report of several particles, e.g., `pdgQuery report tau- D0 --format html > report.html`
+    Validate,              // Validate an EvtGen or Pythia decay file against the PDG, e.g., `pdgQuery validate DECAY.DEC --max-pull 3`
+    SlhaCompare,           // Compare the DECAY blocks of an SLHA file with the PDG, e.g., `pdgQuery compare decays.slha`
+    Cite,                  // BibTeX or RIS of the PDG and the papers behind a value, e.g., `pdgQuery cite S035M --ris`
     // PhysicalPropertySearch, // Query for particles matching specific physical properties
     Unknown,               // Unknown query type
 }
@@ -39,7 +54,237 @@ pub fn query_verify(args: &[&str]) -> Result<(), QueryError> {
     Ok(()) // If all queries succeed, return Ok
 }
 
+// Remove `--flag value` from the arguments and return the value
+pub fn take_option<'a>(args: &mut Vec<&'a str>, flag: &str) -> Option<&'a str>{
+    let position = args.iter().position(|&arg| arg == flag)?;
+    args.remove(position);
+    if position < args.len() {
+        Some(args.remove(position))
+    } else {
+        None
+    }
+}
+
+// Remove a `--flag` without value from the arguments and return whether it was there
+pub fn take_flag(args: &mut Vec<&str>, flag: &str) -> bool{
+    let position = args.iter().position(|&arg| arg == flag);
+    if let Some(position) = position {
+        args.remove(position);
+    }
+    position.is_some()
+}
+
+fn parse_option<T: std::str::FromStr>(args: &mut Vec<&str>, flag: &str) -> Result<Option<T>, QueryError>{
+    match take_option(args, flag) {
+        Some(value) => value
+            .parse::<T>()
+            .map(Some)
+            .map_err(|_| QueryError(format!("Invalid value {} for {}", value, flag))),
+        None => Ok(None),
+    }
+}
+
+// Options of `pdgQuery tree`: --depth N, --min-prob P, --ctau MM (or "none") and --stable a,b,c
+pub fn decay_tree_options(args: &mut Vec<&str>) -> Result<DecayTreeConfig, QueryError>{
+    let mut config = DecayTreeConfig::default();
+    if let Some(depth) = parse_option(args, "--depth")? {
+        config.max_depth = depth;
+    }
+    if let Some(min_probability) = parse_option(args, "--min-prob")? {
+        config.min_probability = min_probability;
+    }
+    if let Some(ctau) = take_option(args, "--ctau") {
+        config.ctau_threshold = match ctau {
+            "none" => None,
+            _ => Some(ctau.parse::<f64>().map_err(|_| QueryError(format!("Invalid value {} for --ctau", ctau)))?),
+        };
+    }
+    if let Some(stable) = take_option(args, "--stable") {
+        config.stable_particles = stable.split(',').map(|name| name.to_string()).collect();
+    }
+    Ok(config)
+}
+
+pub struct SampleOptions{
+    pub events: usize,
+    pub seed: u64,
+    pub recursive: bool,
+    pub momenta: Option<EventFormat>, // None writes JSON lines without momenta
+}
+
+// Options of `pdgQuery sample`: --events N, --seed S, --recursive to also decay unstable daughters
+// and --output json|hepmc|lhe, the last two generate four-momenta
+pub fn sample_options(args: &mut Vec<&str>) -> Result<SampleOptions, QueryError>{
+    let momenta = match take_option(args, "--output") {
+        None | Some("json") => None,
+        Some("hepmc") => Some(EventFormat::HepMC3),
+        Some("lhe") => Some(EventFormat::Lhe),
+        Some(output) => return Err(QueryError(format!("Unknown output {}, use json, hepmc or lhe", output))),
+    };
+    Ok(SampleOptions{
+        events: parse_option(args, "--events")?.unwrap_or(10),
+        seed: parse_option(args, "--seed")?.unwrap_or(1),
+        recursive: take_flag(args, "--recursive"),
+        momenta,
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OutputFormat{
+    Text,  // aligned tables for the terminal
+    Latex, // tabular environments with math mode names
+    Markdown, // GFM tables
+    Html,  // a standalone page with embedded CSS
+}
+
+// --format text|latex|markdown|html
+pub fn output_format(args: &mut Vec<&str>) -> Result<OutputFormat, QueryError>{
+    match take_option(args, "--format") {
+        None | Some("text") => Ok(OutputFormat::Text),
+        Some("latex") => Ok(OutputFormat::Latex),
+        Some("markdown") | Some("md") => Ok(OutputFormat::Markdown),
+        Some("html") => Ok(OutputFormat::Html),
+        Some(format) => Err(QueryError(format!("Unknown format {}, use text, latex, markdown or html", format))),
+    }
+}
+
+// --ascii writes plain PDG names and ASCII symbols, the default when stdout is not a UTF-8 terminal,
+// --color highlights headers, limits and large errors of the text output
+pub fn output_profile(args: &mut Vec<&str>, format: OutputFormat) -> OutputProfile{
+    let ascii = take_flag(args, "--ascii");
+    let color = take_flag(args, "--color");
+    match format {
+        OutputFormat::Text => OutputProfile::detect(ascii, color),
+        // documents are UTF-8 whatever the terminal
+        _ => OutputProfile{ unicode: !ascii, color: false },
+    }
+}
+
+// --unit MeV,ps writes measurements in these units, a width can also be written as a lifetime or a decay length
+pub fn output_units(args: &mut Vec<&str>) -> Result<Vec<Unit>, QueryError>{
+    match take_option(args, "--unit") {
+        Some(units) => units
+            .split(',')
+            .map(|unit| Unit::parse(unit).ok_or(QueryError(format!("Unknown unit {}", unit))))
+            .collect(),
+        None => Ok(Vec::new()),
+    }
+}
+
+// --only mass,lifetime keeps these measurement sections of a particle, "decays" keeps its decay table
+pub fn section_filter(args: &mut Vec<&str>) -> Vec<String>{
+    take_option(args, "--only")
+        .map(|sections| sections.split(',').filter(|section| !section.is_empty()).map(|section| section.to_string()).collect())
+        .unwrap_or_default()
+}
+
+// --details lists the measurements behind each summary value of a particle, best with --only
+pub fn details_mode(args: &mut Vec<&str>) -> bool{
+    take_flag(args, "--details")
+}
+
+// --names evtgen renders decays in another naming convention
+pub fn name_convention(args: &mut Vec<&str>) -> Result<Option<Convention>, QueryError>{
+    match take_option(args, "--names") {
+        Some(convention) => convention.parse::<Convention>().map(Some).map_err(QueryError),
+        None => Ok(None),
+    }
+}
+
+// Option of `pdgQuery validate`: --max-pull N, the largest accepted |pull| of a branching ratio
+pub fn max_pull(args: &mut Vec<&str>) -> Result<f64, QueryError>{
+    match parse_option::<f64>(args, "--max-pull")? {
+        Some(pull) if pull <= 0.0 => Err(QueryError(format!("--max-pull must be positive, got {}", pull))),
+        pull => Ok(pull.unwrap_or(3.0)),
+    }
+}
+
+// Format flag of `pdgQuery export`, exactly one is required
+pub fn export_format(args: &mut Vec<&str>) -> Result<ExportFormat, QueryError>{
+    let formats = [
+        ("--evtgen", ExportFormat::EvtGen),
+        ("--pythia", ExportFormat::Pythia),
+        ("--root", ExportFormat::Root),
+        ("--heppdt", ExportFormat::HepPdt),
+        ("--slha", ExportFormat::Slha),
+    ];
+    let chosen = formats
+        .iter()
+        .filter(|(flag, _)| take_flag(args, flag))
+        .map(|(_, format)| *format)
+        .collect::<Vec<ExportFormat>>();
+    match chosen.as_slice() {
+        [format] => Ok(*format),
+        _ => Err(QueryError("Choose one export format: --evtgen, --pythia, --root, --heppdt or --slha".to_string())),
+    }
+}
+
+// Option of `pdgQuery cite`: --ris for reference managers, BibTeX otherwise
+pub fn citation_format(args: &mut Vec<&str>) -> CitationFormat{
+    let ris = take_flag(args, "--ris");
+    let bibtex = take_flag(args, "--bibtex");
+    if ris && !bibtex { CitationFormat::Ris } else { CitationFormat::BibTex }
+}
+
+// Decay of `pdgQuery check`, one parent on the left of the arrow and its daughters on the right
+pub fn decay_sides<'a>(decay: &[&'a str]) -> Result<(&'a str, Vec<&'a str>), QueryError>{
+    let arrow = decay.iter().position(|&item| item == "->").ok_or(QueryError("A decay needs an arrow, i.e., \"tau- -> mu- gamma\"".to_string()))?;
+    let (parent, daughters) = (&decay[..arrow], &decay[arrow + 1..]);
+    match parent {
+        [parent] if !daughters.is_empty() && !daughters.contains(&"->") => Ok((parent, daughters.to_vec())),
+        [] => Err(QueryError("A decay needs a parent on the left of ->".to_string())),
+        [_] if daughters.is_empty() => Err(QueryError("A decay needs daughters on the right of ->".to_string())),
+        [_] => Err(QueryError("A decay has a single ->".to_string())),
+        _ => Err(QueryError(format!("A decay has one parent, got {}", parent.join(" ")))),
+    }
+}
+
 pub fn query_type_classifier(user_input: &[&str]) -> QueryType{
+    if user_input.first() == Some(&"export") {
+        if user_input.len() > 1 {
+            return QueryType::Export;
+        }
+        return QueryType::Unknown;
+    }
+    if user_input.first() == Some(&"tree") || user_input.first() == Some(&"sample") {
+        if user_input.len() != 2 {
+            return QueryType::Unknown;
+        }
+        if user_input[0] == "tree" {
+            return QueryType::DecayTree;
+        }
+        return QueryType::DecaySample;
+    }
+    if user_input.first() == Some(&"report") {
+        if user_input.len() > 1 {
+            return QueryType::Report;
+        }
+        return QueryType::Unknown;
+    }
+    if user_input.first() == Some(&"validate") {
+        if user_input.len() > 1 {
+            return QueryType::Validate;
+        }
+        return QueryType::Unknown;
+    }
+    if user_input.first() == Some(&"compare") {
+        if user_input.len() == 2 {
+            return QueryType::SlhaCompare;
+        }
+        return QueryType::Unknown;
+    }
+    if user_input.first() == Some(&"cite") {
+        if user_input.len() > 1 {
+            return QueryType::Cite;
+        }
+        return QueryType::Unknown;
+    }
+    if user_input.first() == Some(&"check") {
+        if user_input.contains(&"->") && user_input.len() > 3 {
+            return QueryType::ConservationCheck;
+        }
+        return QueryType::Unknown;
+    }
     if user_input.len() == 1 {
         return QueryType::SingleParticle;
     }
@@ -109,10 +354,181 @@ mod test {
         let user_input = vec!["tau+", "tau-"];
         assert_eq!(query_type_classifier(&user_input), QueryType::Unknown);
 
+        let user_input = vec!["check", "tau-", "->", "mu-", "gamma"];
+        assert_eq!(query_type_classifier(&user_input), QueryType::ConservationCheck);
+
+        let user_input = vec!["check", "tau-"];
+        assert_eq!(query_type_classifier(&user_input), QueryType::Unknown);
+
+        let user_input = vec!["tree", "D*(2010)+"];
+        assert_eq!(query_type_classifier(&user_input), QueryType::DecayTree);
+
+        let user_input = vec!["sample", "tau-"];
+        assert_eq!(query_type_classifier(&user_input), QueryType::DecaySample);
+
+        let user_input = vec!["export", "--evtgen", "D0", "D+"];
+        assert_eq!(query_type_classifier(&user_input), QueryType::Export);
+
+        let user_input = vec!["export", "--root"];
+        assert_eq!(query_type_classifier(&user_input), QueryType::Export);
+
+        let user_input = vec!["compare", "decays.slha"];
+        assert_eq!(query_type_classifier(&user_input), QueryType::SlhaCompare);
+
+        let user_input = vec!["compare"];
+        assert_eq!(query_type_classifier(&user_input), QueryType::Unknown);
+
+        let user_input = vec!["report", "tau-", "D0"];
+        assert_eq!(query_type_classifier(&user_input), QueryType::Report);
+
+        let user_input = vec!["validate", "DECAY.DEC", "--max-pull", "2"];
+        assert_eq!(query_type_classifier(&user_input), QueryType::Validate);
+
         // let user_input = vec!["pdgQuery" .to_string(), "tau+".to_string(), "tau-".to_string(), "tau+".to_string()];
 
     }
 
+    #[test]
+    fn test_take_option(){
+        let mut args = vec!["tree", "D0", "--depth", "2", "--ascii"];
+        assert_eq!(take_option(&mut args, "--depth"), Some("2"));
+        assert_eq!(take_option(&mut args, "--ctau"), None);
+        assert!(take_flag(&mut args, "--ascii"));
+        assert!(!take_flag(&mut args, "--ascii"));
+        assert_eq!(args, vec!["tree", "D0"]);
+
+        let mut args = vec!["tree", "D0", "--depth"];
+        assert_eq!(take_option(&mut args, "--depth"), None);
+        assert_eq!(args, vec!["tree", "D0"]);
+    }
+
+    #[test]
+    fn test_decay_tree_options(){
+        let mut args = vec!["tree", "D0", "--depth", "2", "--ctau", "none", "--stable", "K-,pi+"];
+        let config = decay_tree_options(&mut args).unwrap();
+        assert_eq!(args, vec!["tree", "D0"]);
+        assert_eq!(config.max_depth, 2);
+        assert_eq!(config.ctau_threshold, None);
+        assert_eq!(config.stable_particles, vec!["K-".to_string(), "pi+".to_string()]);
+
+        let mut args = vec!["tree", "D0", "--min-prob", "often"];
+        assert!(decay_tree_options(&mut args).is_err());
+    }
+
+    #[test]
+    fn test_sample_options(){
+        let mut args = vec!["sample", "tau-", "--seed", "7", "--recursive"];
+        let options = sample_options(&mut args).unwrap();
+        assert_eq!(args, vec!["sample", "tau-"]);
+        assert_eq!((options.events, options.seed, options.recursive), (10, 7, true));
+        assert_eq!(options.momenta, None);
+
+        let mut args = vec!["sample", "tau-", "--output", "hepmc"];
+        assert_eq!(sample_options(&mut args).unwrap().momenta, Some(EventFormat::HepMC3));
+        let mut args = vec!["sample", "tau-", "--output", "root"];
+        assert!(sample_options(&mut args).is_err());
+    }
+
+    #[test]
+    fn test_export_format(){
+        let mut args = vec!["D0", "--evtgen", "D+"];
+        assert_eq!(export_format(&mut args).unwrap(), ExportFormat::EvtGen);
+        assert_eq!(args, vec!["D0", "D+"]);
+        let mut args = vec!["D0"];
+        assert!(export_format(&mut args).is_err());
+        let mut args = vec!["--pythia", "D0", "--evtgen"];
+        assert!(export_format(&mut args).is_err());
+    }
+
+    #[test]
+    fn test_output_format(){
+        let mut args = vec!["tau-", "--format", "latex"];
+        assert_eq!(output_format(&mut args).unwrap(), OutputFormat::Latex);
+        assert_eq!(args, vec!["tau-"]);
+        assert_eq!(output_format(&mut args).unwrap(), OutputFormat::Text);
+        let mut args = vec!["--format", "html"];
+        assert_eq!(output_format(&mut args).unwrap(), OutputFormat::Html);
+        let mut args = vec!["--format", "pdf"];
+        assert!(output_format(&mut args).is_err());
+    }
+
+    #[test]
+    fn test_output_profile(){
+        let mut args = vec!["tau-", "--ascii", "--color"];
+        assert_eq!(output_profile(&mut args, OutputFormat::Text), OutputProfile{ unicode: false, color: true });
+        assert_eq!(args, vec!["tau-"]);
+        assert_eq!(output_profile(&mut args, OutputFormat::Html), OutputProfile{ unicode: true, color: false });
+        let mut args = vec!["tau-", "--color"];
+        assert!(!output_profile(&mut args, OutputFormat::Markdown).color);
+    }
+
+    #[test]
+    fn test_output_units(){
+        let mut args = vec!["tau-", "--unit", "MeV,ps"];
+        let units = output_units(&mut args).unwrap();
+        assert_eq!(units.iter().map(|unit| unit.to_string()).collect::<Vec<String>>(), vec!["MeV", "ps"]);
+        assert_eq!(args, vec!["tau-"]);
+        assert!(output_units(&mut args).unwrap().is_empty());
+        let mut args = vec!["--unit", "furlong"];
+        assert!(output_units(&mut args).is_err());
+    }
+
+    #[test]
+    fn test_section_filter(){
+        let mut args = vec!["tau-", "--only", "mass,lifetime,"];
+        assert_eq!(section_filter(&mut args), vec!["mass", "lifetime"]);
+        assert_eq!(args, vec!["tau-"]);
+        assert!(section_filter(&mut args).is_empty());
+    }
+
+    #[test]
+    fn test_details_mode(){
+        let mut args = vec!["tau-", "--details", "--only", "mass"];
+        assert!(details_mode(&mut args));
+        assert_eq!(args, vec!["tau-", "--only", "mass"]);
+        assert!(!details_mode(&mut args));
+    }
+
+    #[test]
+    fn test_citation_format(){
+        let mut args = vec!["S035M", "--ris"];
+        assert_eq!(citation_format(&mut args), CitationFormat::Ris);
+        assert_eq!(args, vec!["S035M"]);
+        assert_eq!(citation_format(&mut args), CitationFormat::BibTex);
+        assert_eq!(query_type_classifier(&["cite", "S035M", "--ris"]), QueryType::Cite);
+        assert_eq!(query_type_classifier(&["cite"]), QueryType::Unknown);
+    }
+
+    #[test]
+    fn test_decay_sides(){
+        assert_eq!(decay_sides(&["tau-", "->", "mu-", "gamma"]).unwrap(), ("tau-", vec!["mu-", "gamma"]));
+        assert!(decay_sides(&["->", "mu-", "gamma"]).is_err());
+        assert!(decay_sides(&["tau-", "->"]).is_err());
+        assert!(decay_sides(&["tau-", "mu-", "gamma"]).is_err());
+        assert!(decay_sides(&["tau-", "nu_tau", "->", "pi-"]).is_err());
+        assert!(decay_sides(&["tau-", "->", "pi-", "->", "pi0"]).is_err());
+    }
+
+    #[test]
+    fn test_name_convention(){
+        let mut args = vec!["D0", "->", "?", "--names", "geant4"];
+        assert_eq!(name_convention(&mut args).unwrap(), Some(Convention::Geant4));
+        assert_eq!(args, vec!["D0", "->", "?"]);
+        assert_eq!(name_convention(&mut args).unwrap(), None);
+        let mut args = vec!["--names", "fluka"];
+        assert!(name_convention(&mut args).is_err());
+    }
+
+    #[test]
+    fn test_max_pull(){
+        let mut args = vec!["DECAY.DEC", "--max-pull", "2.5"];
+        assert_eq!(max_pull(&mut args).unwrap(), 2.5);
+        assert_eq!(args, vec!["DECAY.DEC"]);
+        assert_eq!(max_pull(&mut args).unwrap(), 3.0);
+        let mut args = vec!["--max-pull", "-1"];
+        assert!(max_pull(&mut args).is_err());
+    }
+
     #[test]
     fn test_query_verify(){
         let user_input = vec!["?", "->", "e+", "nu_e", "?*"];
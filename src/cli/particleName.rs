@@ -0,0 +1,231 @@
+// PDG particle names follow a small grammar, e.g., K*(892)bar0, D(s)*+, nubar_e, eta'(958), chi(c1)(1P), Sigma(c)(2455)++:
+// a base symbol, an optional bar, subscripts in short parentheses or after "_", stars and primes,
+// a mass or state label in parentheses and a trailing charge
+
+use crate::pdgdb::naming::name_table;
+
+// Greek letters spelled out in PDG names, only the capitals that differ from Latin ones
+pub const GREEK: [(&str, &str); 32] = [
+    ("alpha", "α"), ("beta", "β"), ("gamma", "γ"), ("delta", "δ"), ("epsilon", "ε"), ("zeta", "ζ"),
+    ("eta", "η"), ("theta", "θ"), ("iota", "ι"), ("kappa", "κ"), ("lambda", "λ"), ("mu", "μ"),
+    ("nu", "ν"), ("xi", "ξ"), ("pi", "π"), ("rho", "ρ"), ("sigma", "σ"), ("tau", "τ"),
+    ("upsilon", "υ"), ("phi", "φ"), ("chi", "χ"), ("psi", "ψ"), ("omega", "ω"),
+    ("Gamma", "Γ"), ("Delta", "Δ"), ("Theta", "Θ"), ("Lambda", "Λ"), ("Xi", "Ξ"),
+    ("Sigma", "Σ"), ("Upsilon", "Υ"), ("Phi", "Φ"), ("Omega", "Ω"),
+];
+
+#[derive(Debug, Default, PartialEq)]
+pub struct ParticleName{
+    pub base: String, // i.e., "K", "pi", "J/psi"
+    pub bar: bool,
+    pub subscript: String, // i.e., "s" of D(s)+, "e" of nu_e, "c1" of chi(c1)(1P)
+    pub underscore: bool, // the subscript is written nu_e rather than D(s)
+    pub star: bool,
+    pub prime: bool,
+    pub label: String, // mass or state label without the parentheses, i.e., "892" or "1S"
+    pub charge: String, // "+", "-", "0", "++", "--" or empty
+}
+
+pub fn greek_symbol(name: &str) -> Option<&'static str>{
+    GREEK.iter().find(|(greek, _)| *greek == name).map(|(_, symbol)| *symbol)
+}
+
+pub fn is_greek(name: &str) -> bool{
+    greek_symbol(name).is_some()
+}
+
+// The base symbol, whether it is barred and the rest of the name, i.e., "K", true and "0" for Kbar0.
+// J/psi is the only base with a slash.
+fn split_base(name: &str) -> (&str, bool, &str){
+    let base_length = if name.starts_with("J/psi") {
+        5
+    } else {
+        name.find(|c: char| !c.is_ascii_alphabetic()).unwrap_or(name.len())
+    };
+    let (base, rest) = name.split_at(base_length);
+    match base.strip_suffix("bar").filter(|stripped| !stripped.is_empty()) {
+        Some(stripped) => (stripped, true, rest),
+        None => (base, false, rest),
+    }
+}
+
+// Greek letters and the bases of the PDG names in the name table, so words like "of" or "to" are not names
+fn is_known_base(base: &str) -> bool{
+    is_greek(base) || name_table().pdg_names().any(|name| split_base(name).0 == base)
+}
+
+// A label is a mass or a state like 1S, 2P, a subscript is short like s, c, 0, c1 or cJ
+fn is_label(group: &str) -> bool{
+    let digits = group.chars().filter(|c| c.is_ascii_digit()).count();
+    digits >= 3 || (group.len() == 2 && group.starts_with(|c: char| c.is_ascii_digit()) && group.ends_with(|c: char| c.is_ascii_uppercase()))
+}
+
+impl ParticleName{
+    // None when the name does not follow the grammar, i.e., for words of a description like "MASS" or "anything"
+    pub fn parse(name: &str) -> Option<ParticleName>{
+        let (base, bar, mut rest) = split_base(name);
+        if base.is_empty() || !is_known_base(base) {
+            return None;
+        }
+        let mut parsed = ParticleName{ base: base.to_string(), bar, ..Default::default() };
+
+        while !rest.is_empty() {
+            if let Some(after) = rest.strip_prefix('(') {
+                let end = after.find(')')?;
+                let group = &after[..end];
+                if group.is_empty() {
+                    return None;
+                }
+                if is_label(group) {
+                    parsed.label = group.to_string();
+                } else {
+                    parsed.subscript = group.to_string();
+                }
+                rest = &after[end + 1..];
+            } else if let Some(after) = rest.strip_prefix('*') {
+                parsed.star = true;
+                rest = after;
+            } else if let Some(after) = rest.strip_prefix('\'') {
+                parsed.prime = true;
+                rest = after;
+            } else if let Some(after) = rest.strip_prefix("bar") {
+                parsed.bar = true;
+                rest = after;
+            } else if let Some(after) = rest.strip_prefix('_') {
+                let end = after.find(|c: char| !c.is_ascii_alphanumeric()).unwrap_or(after.len());
+                parsed.subscript = after[..end].to_string();
+                parsed.underscore = true;
+                rest = &after[end..];
+            } else if ["++", "--", "+", "-", "0"].contains(&rest) {
+                parsed.charge = rest.to_string();
+                rest = "";
+            } else {
+                return None;
+            }
+        }
+        Some(parsed)
+    }
+
+    // PDG notation with Greek letters and a combining overline for the bar, i.e., K*(892)bar0 gives K̅*(892)0
+    pub fn to_unicode(&self) -> String{
+        let mut text = match greek_symbol(&self.base) {
+            Some(symbol) => symbol.to_string(),
+            None => self.base.replace("psi", "ψ"),
+        };
+        if self.bar {
+            text.push('\u{0305}');
+        }
+        if self.underscore {
+            text.push_str(&format!("_{}", self.subscript));
+        } else if !self.subscript.is_empty() {
+            text.push_str(&format!("({})", self.subscript));
+        }
+        if self.star {
+            text.push('*');
+        }
+        if self.prime {
+            text.push('\'');
+        }
+        if !self.label.is_empty() {
+            text.push_str(&format!("({})", self.label));
+        }
+        text.push_str(&self.charge);
+        text
+    }
+
+    pub fn to_latex(&self) -> String{
+        let base = if is_greek(&self.base) {
+            format!("\\{}", self.base)
+        } else {
+            self.base.replace("psi", "\\psi")
+        };
+        let mut latex = if self.bar { format!("\\bar{{{}}}", base) } else { base };
+        if !self.subscript.is_empty() {
+            latex.push_str(&format!("_{{{}}}", self.subscript));
+        }
+        let mut marks = String::new();
+        if self.star {
+            marks.push('*');
+        }
+        if self.prime {
+            marks.push_str("\\prime");
+        }
+        // without a label the charge shares the superscript, D(s)*+ gives D_{s}^{*+}
+        if self.label.is_empty() {
+            marks.push_str(&self.charge);
+            if !marks.is_empty() {
+                latex.push_str(&format!("^{{{}}}", marks));
+            }
+            return latex;
+        }
+        if !marks.is_empty() {
+            latex.push_str(&format!("^{{{}}}", marks));
+        }
+        latex.push_str(&format!("({})", self.label));
+        if !self.charge.is_empty() {
+            latex.push_str(&format!("^{{{}}}", self.charge));
+        }
+        latex
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn latex(name: &str) -> String{
+        ParticleName::parse(name).unwrap().to_latex()
+    }
+
+    #[test]
+    fn test_parse(){
+        let parsed = ParticleName::parse("K*(892)bar0").unwrap();
+        assert_eq!(parsed, ParticleName{
+            base: "K".to_string(),
+            bar: true,
+            star: true,
+            label: "892".to_string(),
+            charge: "0".to_string(),
+            ..Default::default()
+        });
+        assert_eq!(ParticleName::parse("chi(c1)(1P)").unwrap().subscript, "c1");
+        assert_eq!(ParticleName::parse("chi(c1)(1P)").unwrap().label, "1P");
+        assert_eq!(ParticleName::parse("MASS"), None);
+        assert_eq!(ParticleName::parse("anything"), None);
+        assert_eq!(ParticleName::parse("pi+pi"), None);
+        // short words of a description are no bases
+        assert_eq!(ParticleName::parse("of"), None);
+        assert_eq!(ParticleName::parse("to"), None);
+        assert_eq!(ParticleName::parse("in"), None);
+    }
+
+    #[test]
+    fn test_to_unicode(){
+        let unicode = |name: &str| ParticleName::parse(name).unwrap().to_unicode();
+        assert_eq!(unicode("pi+"), "π+");
+        assert_eq!(unicode("Kbar0"), "K\u{0305}0");
+        assert_eq!(unicode("nubar_e"), "ν\u{0305}_e");
+        assert_eq!(unicode("K*(892)bar0"), "K\u{0305}*(892)0");
+        assert_eq!(unicode("xi(c)+"), "ξ(c)+");
+        assert_eq!(unicode("Xi(c)+"), "Ξ(c)+");
+        assert_eq!(unicode("omega(782)"), "ω(782)");
+        assert_eq!(unicode("Omega-"), "Ω-");
+        assert_eq!(unicode("eta'(958)"), "η'(958)");
+        assert_eq!(unicode("J/psi(1S)"), "J/ψ(1S)");
+    }
+
+    #[test]
+    fn test_to_latex(){
+        assert_eq!(latex("pi+"), "\\pi^{+}");
+        assert_eq!(latex("K*(892)bar0"), "\\bar{K}^{*}(892)^{0}");
+        assert_eq!(latex("D(s)*+"), "D_{s}^{*+}");
+        assert_eq!(latex("B(s)bar0"), "\\bar{B}_{s}^{0}");
+        assert_eq!(latex("nubar_e"), "\\bar{\\nu}_{e}");
+        assert_eq!(latex("eta'(958)"), "\\eta^{\\prime}(958)");
+        assert_eq!(latex("J/psi(1S)"), "J/\\psi(1S)");
+        assert_eq!(latex("Sigma(c)(2455)++"), "\\Sigma_{c}(2455)^{++}");
+        assert_eq!(latex("f(0)(980)"), "f_{0}(980)");
+        assert_eq!(latex("Lambdabar"), "\\bar{\\Lambda}");
+        assert_eq!(latex("Omega-"), "\\Omega^{-}");
+    }
+}
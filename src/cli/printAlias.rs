@@ -1,15 +1,109 @@
 use std::collections::HashMap;
+use std::io::IsTerminal;
+use crate::cli::particleName::ParticleName;
+
+/// How the printer writes symbols and whether it colors its output
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OutputProfile {
+    pub unicode: bool, // Greek letters, bars, ± and ×, otherwise plain PDG names and ASCII symbols
+    pub color: bool,   // ANSI colors for headers, limits and large errors
+}
+
+/// What the printer highlights in color mode
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Style {
+    Header,
+    Limit,
+    LargeError,
+}
+
+impl Default for OutputProfile {
+    fn default() -> OutputProfile {
+        OutputProfile { unicode: true, color: false }
+    }
+}
+
+/// The locale variables in the order the C library reads them, the first one set decides
+fn is_utf8_locale() -> bool {
+    ["LC_ALL", "LC_CTYPE", "LANG"]
+        .iter()
+        .filter_map(|variable| std::env::var(variable).ok())
+        .find(|value| !value.is_empty())
+        .is_some_and(|value| {
+            let value = value.to_lowercase();
+            value.contains("utf-8") || value.contains("utf8")
+        })
+}
+
+impl OutputProfile {
+    /// Unicode only when stdout is a terminal with a UTF-8 locale, so pipes and files get ASCII
+    pub fn detect(ascii: bool, color: bool) -> OutputProfile {
+        OutputProfile {
+            unicode: !ascii && std::io::stdout().is_terminal() && is_utf8_locale(),
+            color,
+        }
+    }
+
+    pub fn plus_minus(&self) -> &'static str {
+        if self.unicode { "±" } else { "+-" }
+    }
+
+    /// The power of ten after a mantissa, i.e., "1.2 × E-5" or "1.2 x10^-5"
+    pub fn times_ten(&self) -> &'static str {
+        if self.unicode { " × E" } else { " x10^" }
+    }
+
+    pub fn sigma(&self) -> &'static str {
+        if self.unicode { "σ" } else { " sigma" }
+    }
+
+    /// The branch of a tree line
+    pub fn branch(&self, is_last: bool) -> &'static str {
+        match (self.unicode, is_last) {
+            (true, true) => "└── ",
+            (true, false) => "├── ",
+            (false, true) => "`-- ",
+            (false, false) => "|-- ",
+        }
+    }
+
+    /// The indent below a branch, continuing the line of the branches that follow
+    pub fn indent(&self, is_last: bool) -> &'static str {
+        match (self.unicode, is_last) {
+            (_, true) => "    ",
+            (true, false) => "│   ",
+            (false, false) => "|   ",
+        }
+    }
+
+    pub fn paint(&self, text: &str, style: Style) -> String {
+        if !self.color {
+            return text.to_string();
+        }
+        let code = match style {
+            Style::Header => "1",
+            Style::Limit => "33",
+            Style::LargeError => "31",
+        };
+        format!("\x1b[{}m{}\x1b[0m", code, text)
+    }
+}
 
 /// A struct for handling physics unit formatting
 pub struct QueryAlias {
     pub unit_aliases: HashMap<&'static str, &'static str>,
     pub particle_display_aliases: Vec<(&'static str, &'static str)>,
+    pub profile: OutputProfile,
     // reverse_aliases: HashMap<String, String>,
 }
 
 impl QueryAlias {
     /// Create a new PhysicsUnits instance with predefined unit aliases
     pub fn new() -> QueryAlias {
+        QueryAlias::with_profile(OutputProfile::default())
+    }
+
+    pub fn with_profile(profile: OutputProfile) -> QueryAlias {
             let unit_aliases: HashMap<&'static str, &'static str> = {
                 let mut unit_aliases = HashMap::new();
                 // Basic units
@@ -59,38 +153,97 @@ impl QueryAlias {
 
                 unit_aliases   
             };
-        let particle_display_aliases: Vec<(&'static str, &'static str)> = {
-            let mut particle_display_aliases: Vec<(&'static str, &'static str)> = Vec::new();
-            particle_display_aliases.push(("nubar", "ν̅"));
-            particle_display_aliases.push(("Kbar", "K̅"));
-            particle_display_aliases.push(("mu", "μ"));
-            particle_display_aliases.push(("nu", "ν"));
-            particle_display_aliases.push(("tau", "τ"));
-            particle_display_aliases.push(("pi", "π"));
-            particle_display_aliases.push(("rho", "ρ"));
-            particle_display_aliases.push(("omega", "Ω"));
-            particle_display_aliases.push(("phi", "φ"));
-            particle_display_aliases.push(("eta", "η"));
-            particle_display_aliases.push(("xi", "χ"));
-            particle_display_aliases.push(("psi", "ψ"));
-            particle_display_aliases.push(("gamma", "γ"));
-            particle_display_aliases.push(("Delta", "Δ"));
-            particle_display_aliases.push(("Sigma", "Σ"));
-            particle_display_aliases.push(("Lambda", "Λ"));
-            particle_display_aliases.push(("Omega", "Ω"));
-            particle_display_aliases.push(("Xi", "Ξ"));
-            particle_display_aliases.push(("electron", "e"));
-            particle_display_aliases.push(("sigma", "σ"));
-            
-            particle_display_aliases
-        };
+        // whole words that are not particle names, the names themselves are rendered by ParticleName
+        let particle_display_aliases: Vec<(&'static str, &'static str)> = vec![("electron", "e")];
 
         QueryAlias {
             unit_aliases,
             particle_display_aliases,
+            profile,
         }   
 
     }
+
+    /// Render the particle names of a description with Greek letters and bars, whole tokens only,
+    /// so "muon" and "spin" stay as they are while "mu-" becomes "μ-", the ASCII profile keeps the PDG names
+    pub fn render_description(&self, text: &str) -> String {
+        if !self.profile.unicode {
+            return text.to_string();
+        }
+        let mut rendered = String::with_capacity(text.len());
+        let mut word = String::new();
+        for c in text.chars() {
+            if c.is_whitespace() {
+                rendered.push_str(&self.render_word(&word));
+                rendered.push(c);
+                word.clear();
+            } else {
+                word.push(c);
+            }
+        }
+        rendered.push_str(&self.render_word(&word));
+        rendered
+    }
+
+    /// A word may carry punctuation of the description or a multiplicity around the name, i.e., "(pi+", "pi-)," or "2pi0"
+    fn render_word(&self, word: &str) -> String {
+        if let Some((_, symbol)) = self.particle_display_aliases.iter().find(|(name, _)| *name == word) {
+            return symbol.to_string();
+        }
+        let leading = word.len() - word.trim_start_matches(['(', '[', '{']).trim_start_matches(|c: char| c.is_ascii_digit()).len();
+        let trailing = word.len() - word.trim_end_matches([')', ']', '}', ',', ';', ':']).len();
+        // the name is the longest core that parses, closing parentheses may belong to it as in J/psi(1S)
+        for start in 0..=leading {
+            for end in (word.len().saturating_sub(trailing)..=word.len()).rev() {
+                if start >= end {
+                    continue;
+                }
+                if let Some(name) = ParticleName::parse(&word[start..end]) {
+                    return format!("{}{}{}", &word[..start], name.to_unicode(), &word[end..]);
+                }
+            }
+        }
+        word.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_description() {
+        let aliases = QueryAlias::new();
+        assert_eq!(aliases.render_description("muon spin"), "muon spin");
+        assert_eq!(aliases.render_description("theta beta"), "θ β");
+        assert_eq!(aliases.render_description("mu- --> e- gamma"), "μ- --> e- γ");
+        assert_eq!(aliases.render_description("Xi- --> Lambda pi-"), "Ξ- --> Λ π-");
+        assert_eq!(aliases.render_description("omega(782) pi0"), "ω(782) π0");
+        assert_eq!(aliases.render_description("Kbar0 nubar_e"), "K\u{0305}0 ν\u{0305}_e");
+        assert_eq!(aliases.render_description("(pi+ pi-) K*(892)bar0,"), "(π+ π-) K\u{0305}*(892)0,");
+        assert_eq!(aliases.render_description("J/psi(1S) MASS"), "J/ψ(1S) MASS");
+        assert_eq!(aliases.render_description("electron  pion"), "e  pion");
+        assert_eq!(aliases.render_description("K- 2pi+ (3pi0)"), "K- 2π+ (3π0)");
+        assert_eq!(aliases.render_description("0.5"), "0.5");
+    }
+
+    #[test]
+    fn test_ascii_profile() {
+        let ascii = OutputProfile { unicode: false, color: false };
+        let aliases = QueryAlias::with_profile(ascii);
+        assert_eq!(aliases.render_description("mu- --> e- gamma"), "mu- --> e- gamma");
+        assert_eq!(ascii.plus_minus(), "+-");
+        assert_eq!(ascii.times_ten(), " x10^");
+        assert_eq!(format!("{}{}", ascii.indent(false), ascii.branch(true)), "|   `-- ");
+        assert_eq!(ascii.paint("< 1e-5", Style::Limit), "< 1e-5");
+        assert!(!OutputProfile::detect(true, false).unicode);
+    }
+
+    #[test]
+    fn test_paint() {
+        let color = OutputProfile { unicode: true, color: true };
+        assert_eq!(color.paint("Decay", Style::Header), "\x1b[1mDecay\x1b[0m");
+        assert_eq!(color.paint("< 1e-5", Style::Limit), "\x1b[33m< 1e-5\x1b[0m");
+        assert_eq!(textwrap::core::display_width(&color.paint("1.0", Style::LargeError)), 3);
+    }
 }
-    
-    
\ No newline at end of file
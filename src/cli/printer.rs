@@ -1,187 +1,414 @@
 use crate::pdgdb::{DecayChannel, Particle, ParticleDecay, ParticleMeasurement};
-use crate::cli::printAlias::QueryAlias;
+use crate::pdgdb::conservation::{ConservationReport, Verdict};
+use crate::pdgdb::queries::decayTree::DecayNode;
+use crate::pdgdb::finalStates::{final_states, group_final_states, inclusive_fraction, FinalState};
+use crate::pdgdb::naming::{name_table, Convention};
+use crate::interop::compare::{is_failure, ChannelComparison};
+use crate::cli::printAlias::{OutputProfile, QueryAlias, Style};
+use crate::cli::rounding::RoundedValue;
+use crate::pdgdb::quantity::{Quantity, Unit};
+use crate::pdgdb::measured::{Kind, Measured};
+use crate::pdgdb::dataType::DataType;
+use crate::pdgdb::pdgDoc::LimitType;
+use crate::pdgdb::listings::{Listing, WeightedAverage};
+use crate::cli::ideogram::ideogram;
+use crate::cli::table::{terminal_width, Table};
 use textwrap;
 use std::sync::OnceLock;
 
 static QUERY_ALIAS: OnceLock<QueryAlias> = OnceLock::new();
 
+// Values with a relative error above this are highlighted in color mode
+const LARGE_RELATIVE_ERROR: f64 = 0.25;
+// A recomputed weighted average within this fraction of the quoted error agrees with it, rounding included
+const AVERAGE_TOLERANCE: f64 = 0.2;
+const IDEOGRAM_WIDTH: usize = 50;
+const IDEOGRAM_HEIGHT: usize = 6;
 
-pub fn single_particle_print(particle: &Particle) {
-    println!("Particle Information:");
+fn aliases() -> &'static QueryAlias {
+    QUERY_ALIAS.get_or_init(QueryAlias::new)
+}
+
+// Chooses how everything is printed, only before the first print, later calls are ignored
+pub fn set_output_profile(profile: OutputProfile) {
+    let _ = QUERY_ALIAS.set(QueryAlias::with_profile(profile));
+}
+
+pub fn output_profile() -> OutputProfile {
+    aliases().profile
+}
+
+static OUTPUT_UNITS: OnceLock<Vec<Unit>> = OnceLock::new();
+
+// Units of --unit, only before the first print like the profile
+pub fn set_output_units(units: Vec<Unit>) {
+    let _ = OUTPUT_UNITS.set(units);
+}
+
+pub(crate) fn convert_measurement(measurement: &ParticleMeasurement) -> ParticleMeasurement {
+    measurement_in_units(measurement, OUTPUT_UNITS.get().map_or(&[], |units| units.as_slice()))
+}
+
+// The measurement in the first unit of its dimension. A width without one may become a lifetime or length and
+// a lifetime or c tau a width, where limits swap sides and an unbounded side leaves a limit at the bounded end.
+// Measurements without a matching unit stay as they are.
+fn measurement_in_units(measurement: &ParticleMeasurement, units: &[Unit]) -> ParticleMeasurement {
+    let quantity = match Quantity::from_measurement(measurement) {
+        Some(quantity) => quantity,
+        None => return measurement.clone(),
+    };
+    let inverts = matches!(measurement.kind, DataType::Width | DataType::Lifetime);
+    let target = units
+        .iter()
+        .find(|unit| unit.dimension == quantity.unit.dimension && unit.power == quantity.unit.power)
+        .or_else(|| units.iter().find(|unit| inverts && quantity.unit.inverts(unit)));
+    let converted = match target.and_then(|target| quantity.convert(target)) {
+        Some(converted) => converted,
+        None => return measurement.clone(),
+    };
+    let unbounded = |error: Option<f64>| error.is_some_and(|error| error.is_infinite());
+    let code = |limit: LimitType| Some(limit.code().to_string());
+    let (value, plus_error, minus_error, limit_type) = match measurement.limit_type.as_deref().and_then(LimitType::decode) {
+        Some(LimitType::Upper) if quantity.unit.inverts(&converted.unit) => (converted.value, converted.plus_error, converted.minus_error, code(LimitType::Lower)),
+        Some(LimitType::Lower) if quantity.unit.inverts(&converted.unit) => (converted.value, converted.plus_error, converted.minus_error, code(LimitType::Upper)),
+        _ if unbounded(converted.plus_error) => (converted.value - converted.minus_error.unwrap_or_default(), None, None, code(LimitType::Lower)),
+        _ if unbounded(converted.minus_error) => (converted.value + converted.plus_error.unwrap_or_default(), None, None, code(LimitType::Upper)),
+        _ => (converted.value, converted.plus_error, converted.minus_error, measurement.limit_type.clone()),
+    };
+    ParticleMeasurement {
+        value: Some(value),
+        plus_error,
+        minus_error,
+        unit_text: Some(converted.unit.to_string()),
+        display_value: None,
+        display_power_of_ten: None,
+        limit_type,
+        ..measurement.clone()
+    }
+}
+
+fn print_header(title: &str) {
+    println!("{}", output_profile().paint(title, Style::Header));
     println!("----------------------");
+}
+
+
+pub fn single_particle_print(particle: &Particle) {
+    print_header("Particle Information:");
     println!("Name           : {}", particle.name.clone().unwrap_or("Unknown".to_string()));
     println!("PDG ID         : {}", particle.pdgid.map_or("Unknown".to_string(), |id| id.to_string()));
     println!("Node ID        : {}", particle.node_id.clone().unwrap_or("Unknown".to_string()));
     println!("Charge         : {}", particle.charge.map_or("Unknown".to_string(), |charge| charge.to_string()));
-    println!("J Spin         : {}", particle.j_spin.clone().unwrap_or("Unknown".to_string()));
+    if let Some(mcid) = particle.mcid() {
+        println!("Type           : {}", mcid.kind());
+        println!("Quark Content  : {}", mcid.quark_content_string().unwrap_or("None".to_string()));
+        println!("Antiparticle   : {}", if mcid.is_self_conjugate() { "self-conjugate".to_string() } else { mcid.antiparticle().0.to_string() });
+        let names = name_table()
+            .all_names(mcid.0)
+            .into_iter()
+            .filter(|(convention, _)| *convention != Convention::Pdg)
+            .map(|(convention, name)| format!("{} {}", convention, name))
+            .collect::<Vec<String>>();
+        if !names.is_empty() {
+            println!("Other Names    : {}", names.join(", "));
+        }
+    }
+    match particle.spin_consistent() {
+        Some(false) => println!(
+            "J Spin         : {} (MC ID gives {})",
+            particle.j_spin.clone().unwrap_or("Unknown".to_string()),
+            particle.mcid().and_then(|mcid| mcid.spin_j()).unwrap_or("Unknown".to_string()),
+        ),
+        _ => println!("J Spin         : {}", particle.j_spin.clone().unwrap_or("Unknown".to_string())),
+    }
     println!("I Spin         : {}", particle.i_spin.clone().unwrap_or("Unknown".to_string()));
     println!("Charge Parity  : {}", particle.charge_parity.clone().unwrap_or("Unknown".to_string()));
     println!("Space Parity   : {}", particle.space_parity.clone().unwrap_or("Unknown".to_string()));
     println!("G Parity       : {}", particle.g_parity.clone().unwrap_or("Unknown".to_string()));
     println!("----------------------");
     if let Some(decays) = &particle.decay {
-        println!("Decay Information:");
-        println!("----------------------");
-        print_decay_header();
+        print_header("Decay Information:");
+        let mut table = Table::new(&["Decay", "Value", "(+Error, -Error)"]).wrap(0);
+        let mut markers = FootnoteMarkers::default();
         for decay in decays {
-            print_decay_info(decay);
+            table.add_row(decay_row(decay, &mut markers));
         }
+        table.print();
+        markers.print(particle);
         println!("----------------------");
     }
-    if let Some(measurement) = &particle.measurements {
-        println!("Measurement Information:");
+    if let Some(measurements) = &particle.measurements {
+        print_header("Measurement Information:");
+        let mut markers = FootnoteMarkers::default();
+        for (section, measurements) in measurement_sections(measurements) {
+            println!("{}", output_profile().paint(&section, Style::Header));
+            let mut table = Table::new(&["Description", "Rounded Value", "Unit", "Precise Value", "(+Error, -Error)"]).wrap(0);
+            for measurement in measurements {
+                table.add_row(measurement_row(measurement, &mut markers));
+            }
+            table.print();
+        }
+        markers.print(particle);
         println!("----------------------");
-        print_measurement_header();
-        for measurement in measurement {
-            print_measurement_info(measurement);
+    }
+    let legend = particle.code_legend();
+    if !legend.is_empty() {
+        print_header("Codes:");
+        for (code, meaning) in legend {
+            println!("{:<15}: {}", code, meaning);
         }
         println!("----------------------");
     }
 }
-fn print_decay_header() {
-    println!(
-        "{:<40} {:<20} {:<10}",
-        "Decay", "Value", "(+Error, -Error)"
-    );
-    println!("{}", "-".repeat(80));
-}
-
-fn print_decay_info(decay: &ParticleDecay) {
-    let display_value = match decay.limit_type.as_deref() {
-        Some("U") => format!("<{:.2e}", decay.value.unwrap_or(f64::NAN)),
-        Some("L") => format!(">{:.2e}", decay.value.unwrap_or(f64::NAN)),
-        _ => {
-            if decay.value.is_some() {
-                format!("{:.2e}", decay.value.unwrap())
-            } 
-            else if decay.display_value.is_some() {
-                decay.display_value.clone().unwrap()
-            }
-            else {
-                "N/A".to_string()
-            }
-        },
-    };
 
-    let description = format_description(&decay.description, 40);
-    let lines: Vec<&str> = description.split('\n').collect();
-    for (i, line) in lines.iter().enumerate() {
-        if i == 0 {
-            println!(
-                "{:<40} {:<20}  (+{:.2e}, -{:.2e})",
-                line,
-                display_value.as_str(),
-                decay.plus_error.map_or(f64::NAN, |x| if x==0.0 {f64::NAN} else {x}),
-                decay.minus_error.map_or(f64::NAN, |x| if x==0.0 {f64::NAN} else {x}),
-            );
-        } else {
-            println!("{:<40}", line);
+// Measurements grouped by data type, masses, widths and lifetimes first and the others in the order of the database
+fn measurement_sections(measurements: &[ParticleMeasurement]) -> Vec<(String, Vec<&ParticleMeasurement>)> {
+    let mut sections: Vec<(String, Vec<&ParticleMeasurement>)> = Vec::new();
+    for measurement in measurements {
+        let section = measurement.kind.section();
+        match sections.iter_mut().find(|(title, _)| *title == section) {
+            Some((_, members)) => members.push(measurement),
+            None => sections.push((section, vec![measurement])),
         }
     }
+    let rank = |members: &Vec<&ParticleMeasurement>| match members[0].kind {
+        DataType::Mass => 0,
+        DataType::Width => 1,
+        DataType::Lifetime => 2,
+        _ => 3,
+    };
+    sections.sort_by_key(|(_, members)| rank(members));
+    sections
 }
 
-fn print_measurement_header() {
-    println!(
-        "{:<70} {:<30} {:<15} {:<15} {:<15}",
-        // "{:<70} {:<30} {:<40} {:<12}",
-        "Description", "Rounded Value", "Unit", "Precise Value", "(+Error, -Error)"
-    );
-    println!("{}", "-".repeat(180));
-}
-
-fn print_measurement_info(measurement: &ParticleMeasurement) {
-    let simplified_value = format_measurement_value(measurement);
-    let description = format_description(&measurement.description, 65);
-    let unit = format_unit(&measurement.unit_text);
-
-    let lines: Vec<&str> = description.split('\n').collect();
-    for (i, line) in lines.iter().enumerate() {
-        if i == 0 {
-            println!(
-                "{:<70} {:<30} {:<15} {:<15} (+{:<8}, -{:<8})",
-                line,
-                simplified_value, // Ensure fixed width for simplified_value
-                unit, // Ensure fixed width for unit
-                format!("{:.6e}", measurement.value.unwrap_or_default()), // Ensure fixed width for value
-                format!("{:.2e}", measurement.plus_error.map_or(f64::NAN, |x| if x == 0.0 { f64::NAN } else { x })), // Ensure fixed width for plus_error
-                format!("{:.2e}", measurement.minus_error.map_or(f64::NAN, |x| if x == 0.0 { f64::NAN } else { x })) // Ensure fixed width for minus_error
-            );
-        } else {
-            println!("{:<70}", line);
+// The listings behind a summary value with their ideogram, and their weighted average checked against the quoted one
+pub fn details_print(measurement: &ParticleMeasurement, listings: &[Listing]) {
+    let listings = listings.iter().map(|listing| listing.in_unit(measurement.unit_text.as_deref())).collect::<Vec<Listing>>();
+    let description = display_text(measurement.description.as_deref().unwrap_or("Unknown"));
+    match format_unit(&measurement.unit_text) {
+        unit if unit.is_empty() => print_header(&format!("Measurements of {}:", description)),
+        unit => print_header(&format!("Measurements of {} in {}:", description, unit)),
+    }
+    let mut table = Table::new(&["Document", "Year", "Value", "(+Error, -Error)", "Technique", "Comment", "Averaged"]).wrap(0);
+    for listing in &listings {
+        table.add_row(listing_row(listing));
+    }
+    table.print();
+
+    let average = WeightedAverage::of(&listings);
+    // like the PDG, only the measurements of the average are drawn
+    let labelled = listings
+        .iter()
+        .filter(|listing| listing.used_in_average)
+        .filter_map(|listing| Some((listing.document_id.clone().unwrap_or(format!("#{}", listing.measurement_id)), listing.measured()?)))
+        .collect::<Vec<(String, Measured)>>();
+    let plot = ideogram(&labelled, average.as_ref().map(|average| &average.average), IDEOGRAM_WIDTH, IDEOGRAM_HEIGHT);
+    if !plot.is_empty() {
+        println!();
+        for line in plot {
+            println!("{}", line);
         }
+        println!();
+    }
+    let quoted = Measured::from_measurement(measurement);
+    match &average {
+        Some(average) => {
+            println!("Weighted average : {} from {} measurements, chi2 = {:.1}, S = {:.1}", format_measured(&average.average), average.count, average.chi2, average.scale_factor);
+            if let Some(quoted) = &quoted {
+                let scale_factor = measurement.scale_factor.map_or(String::new(), |scale_factor| format!(", S = {:.1}", scale_factor));
+                println!("Quoted average   : {}{}", format_measured(quoted), scale_factor);
+            }
+            println!("Check            : {}", average_check(average, quoted.as_ref()));
+        },
+        None => println!("Weighted average : no measurement is used in the average"),
+    }
+    println!("----------------------");
+}
+
+fn listing_row(listing: &Listing) -> Vec<String> {
+    vec![
+        listing.document_id.clone().unwrap_or("Unknown".to_string()),
+        listing.year.map_or("-".to_string(), |year| year.to_string()),
+        listing.measured().map_or("Unknown".to_string(), |measured| highlight_value(format_measured(&measured), Some(measured))),
+        format_error_pair(listing.measured()),
+        listing.technique.clone().unwrap_or_default(),
+        display_text(listing.comment.as_deref().unwrap_or("")),
+        if listing.used_in_average { "yes".to_string() } else { "no".to_string() },
+    ]
+}
+
+// The quoted value may come from a fit or from data that is not listed, so a difference is a hint, not an error
+fn average_check(average: &WeightedAverage, quoted: Option<&Measured>) -> String {
+    match quoted.and_then(|quoted| average.tension(quoted)) {
+        Some(tension) if tension.abs() <= AVERAGE_TOLERANCE => format!("consistent, {:.2} of the quoted error apart", tension.abs()),
+        Some(tension) => output_profile().paint(
+            &format!("differs by {:.2} of the quoted error, the quoted value may come from a fit", tension.abs()),
+            Style::LargeError,
+        ),
+        None => "no quoted central value to compare with".to_string(),
     }
 }
 
-fn format_measurement_value(measurement: &ParticleMeasurement) -> String {
-    match measurement.limit_type.as_deref() {
-        Some("U") => format_limit_value("<", measurement),
-        Some("L") => format_limit_value(">", measurement),
-        Some("R") => format_range_value(measurement),
-        _ => format_standard_value(measurement),
+// Unrounded errors, "-" for limits, ranges and values without errors
+fn format_error_pair(measured: Option<Measured>) -> String {
+    match measured {
+        Some(measured) if measured.kind == Kind::Central && measured.has_errors() => {
+            let error = |error: Option<f64>| error.map_or("?".to_string(), |error| format!("{:.2e}", error));
+            format!("(+{}, -{})", error(measured.plus_error), error(measured.minus_error))
+        },
+        _ => "-".to_string(),
     }
 }
 
-fn format_limit_value(limit_type: &str, measurement: &ParticleMeasurement) -> String {
-    let value = measurement.value.unwrap_or(f64::NAN);
-    let value_order = value.abs().log10().floor() as i32;
-    format!("{} {:.4} x E{:2}", limit_type, value / 10.0_f64.powi(value_order), value_order)
+// Footnotes numbered in the order of their first marker, listed below the table they belong to in every format
+#[derive(Default)]
+pub(crate) struct FootnoteMarkers {
+    ids: Vec<i64>,
 }
 
-fn format_standard_value(measurement: &ParticleMeasurement) -> String {
-    let value = measurement.value.unwrap_or(f64::NAN);
-    let plus_error = measurement.plus_error.unwrap_or(f64::NAN);
-    let minus_error = measurement.minus_error.unwrap_or(f64::NAN);
-    let value_order = value.abs().log10().floor() as i32;
-    let plus_order = plus_error.abs().log10().floor() as i32;
-    let minus_order = minus_error.abs().log10().floor() as i32;
+impl FootnoteMarkers {
+    // The text with the numbers of its footnotes, i.e., "τ MASS [1,2]"
+    pub(crate) fn mark(&mut self, text: String, footnote_ids: &[i64]) -> String {
+        if footnote_ids.is_empty() {
+            return text;
+        }
+        let numbers = footnote_ids
+            .iter()
+            .map(|id| {
+                if !self.ids.contains(id) {
+                    self.ids.push(*id);
+                }
+                (self.ids.iter().position(|known| known == id).unwrap() + 1).to_string()
+            })
+            .collect::<Vec<String>>();
+        format!("{} [{}]", text, numbers.join(","))
+    }
 
-    if (plus_error - minus_error).abs() < f64::EPSILON * plus_error.abs() {
-        format_symmetric_errors(value, plus_error, value_order)
-    } else if value != 0.0 && plus_error != 0.0 && minus_error != 0.0 {
-        format_asymmetric_errors(value, plus_error, minus_error, value_order, plus_order, minus_order)
-    } else {
-        measurement.display_value.clone().unwrap_or("Unknown".to_string())
+    // The number and the PDG text of every marked footnote the particle has
+    pub(crate) fn notes<'a>(&self, particle: &'a Particle) -> Vec<(usize, &'a str)> {
+        self.ids
+            .iter()
+            .enumerate()
+            .filter_map(|(number, id)| Some((number + 1, particle.footnote(*id)?.text.as_str())))
+            .collect()
+    }
+
+    fn lines(&self, particle: &Particle, width: usize) -> Vec<String> {
+        self.notes(particle)
+            .into_iter()
+            .map(|(number, text)| {
+                let options = textwrap::Options::new(width).subsequent_indent("    ");
+                textwrap::fill(&format!("[{}] {}", number, display_text(text)), options)
+            })
+            .collect()
+    }
+
+    fn print(&self, particle: &Particle) {
+        for line in self.lines(particle, terminal_width()) {
+            println!("{}", line);
+        }
     }
 }
 
-fn format_range_value(measurement: &ParticleMeasurement) -> String {
-    let value = measurement.value.unwrap_or(f64::NAN);
-    let plus_error = measurement.plus_error.unwrap_or(f64::NAN);
-    let minus_error = measurement.minus_error.unwrap_or(f64::NAN);
-    format!("{:.4e} to {:.4e}", value + plus_error, value - minus_error)
+fn decay_row(decay: &ParticleDecay, markers: &mut FootnoteMarkers) -> Vec<String> {
+    vec![
+        markers.mark(display_text(decay.description.as_deref().unwrap_or("Unknown")), &decay.footnote_ids),
+        highlight_value(format_decay_value(decay), Measured::from_decay(decay)),
+        format_error_pair(Measured::from_decay(decay)),
+    ]
 }
 
-fn format_symmetric_errors(value: f64, plus_error: f64, value_order: i32) -> String {
-    if value_order == 0 {
-        format!("({:.4} ± {:.4})", value, plus_error)
-    } else {
-        format!("({:.4} ± {:.4}) × E{}", value / 10.0_f64.powi(value_order), plus_error / 10.0_f64.powi(value_order), value_order)
+fn is_large_error(measured: &Measured) -> bool {
+    measured.kind == Kind::Central && measured.value != 0.0 && measured.plus_error.into_iter().chain(measured.minus_error).fold(0.0, f64::max) / measured.value.abs() > LARGE_RELATIVE_ERROR
+}
+
+// Limits and values with large errors stand out in color mode
+fn highlight_value(text: String, measured: Option<Measured>) -> String {
+    let profile = output_profile();
+    match measured {
+        Some(measured) if measured.is_limit() => profile.paint(&text, Style::Limit),
+        Some(measured) if is_large_error(&measured) => profile.paint(&text, Style::LargeError),
+        _ => text,
     }
 }
 
-fn format_asymmetric_errors(value: f64, plus_error: f64, minus_error: f64, value_order: i32, plus_order: i32, minus_order: i32) -> String {
-    if value_order == 0 {
-        format!("({:.5} +{:.5} -{:.5})", value, plus_error, minus_error)
-    } else if (value_order - plus_order).abs() < 3 && (value_order - minus_order).abs() < 3 {
-        format!("({:.4} +{:.4} -{:.4}) x E{}", value / 10.0_f64.powi(value_order), plus_error / 10.0_f64.powi(value_order), minus_error / 10.0_f64.powi(value_order), value_order)
-    } else {
-        format!("{}E{} (+{}E{}, -{}E{})", (value / 10.0_f64.powi(value_order)).round() / 1000.0 * 1000.0, value_order, (plus_error / 10.0_f64.powi(plus_order)).round() / 1000.0 * 1000.0, plus_order, (minus_error / 10.0_f64.powi(minus_order)).round() / 1000.0 * 1000.0, minus_order)
+pub(crate) fn format_decay_value(decay: &ParticleDecay) -> String {
+    match Measured::from_decay(decay) {
+        Some(measured) => format_measured(&measured),
+        None => decay.display_value.clone().unwrap_or("N/A".to_string()),
     }
 }
 
-fn format_description(description: &Option<String>, width: usize) -> String {
-    let aliases = QUERY_ALIAS.get_or_init(|| QueryAlias::new());
-    let mut description = description.clone().unwrap_or("Unknown".to_string());
-    for (name, symbol) in aliases.particle_display_aliases.iter() {
-        description = description.replace(name, symbol);
+fn measurement_row(measurement: &ParticleMeasurement, markers: &mut FootnoteMarkers) -> Vec<String> {
+    let measurement = &convert_measurement(measurement);
+    vec![
+        markers.mark(display_text(measurement.description.as_deref().unwrap_or("Unknown")), &measurement.footnote_ids),
+        highlight_value(format_measurement_value(measurement), Measured::from_measurement(measurement)),
+        format_unit(&measurement.unit_text),
+        format!("{:.6e}", measurement.value.unwrap_or_default()),
+        format_error_pair(Measured::from_measurement(measurement)),
+    ]
+}
+
+// Values without errors keep the PDG display text, i.e., exact or defined quantities
+pub(crate) fn format_measurement_value(measurement: &ParticleMeasurement) -> String {
+    match (Measured::from_measurement(measurement), &measurement.display_value) {
+        (Some(measured), Some(_)) if measured.kind == Kind::Central && !measured.has_errors() => measurement.display_value.clone().unwrap(),
+        (Some(measured), _) => format_measured(&measured),
+        (None, display_value) => display_value.clone().unwrap_or("Unknown".to_string()),
     }
+}
+
+// Limits with their confidence level, i.e., "< 4.2 × E-13 CL=90%", and ranges as "low to high"
+pub(crate) fn format_measured(measured: &Measured) -> String {
+    let confidence = |confidence_level: Option<f64>| {
+        confidence_level.map_or(String::new(), |level| format!(" CL={}%", (level * 1000.0).round() / 10.0))
+    };
+    match measured.kind {
+        Kind::UpperLimit{ confidence_level } => format!("< {}{}", format_rounded(&RoundedValue::new(measured.value, None, None)), confidence(confidence_level)),
+        Kind::LowerLimit{ confidence_level } => format!("> {}{}", format_rounded(&RoundedValue::new(measured.value, None, None)), confidence(confidence_level)),
+        Kind::Range => {
+            let (low, high) = RoundedValue::new(measured.value, measured.plus_error, measured.minus_error).range();
+            format!("{} to {}", low, high)
+        },
+        Kind::Central => format_rounded(&RoundedValue::new(measured.value, measured.plus_error, measured.minus_error)),
+    }
+}
+
+// PDG rounded value with its errors, scaled by a power of ten when it is far from one
+fn format_rounded(rounded: &RoundedValue) -> String {
+    let profile = output_profile();
+    let power = match rounded.exponent() {
+        0 => String::new(),
+        exponent => format!("{}{}", profile.times_ten(), exponent),
+    };
+    match rounded.scaled() {
+        (value, Some(plus), Some(_)) if rounded.is_symmetric() => format!("({} {} {}){}", value, profile.plus_minus(), plus, power),
+        (value, Some(plus), Some(minus)) => format!("({} +{} -{}){}", value, plus, minus, power),
+        (value, Some(plus), None) => format!("({} +{}){}", value, plus, power),
+        (value, None, Some(minus)) => format!("({} -{}){}", value, minus, power),
+        (value, None, None) => format!("{}{}", value, power),
+    }
+}
+
+fn format_description(description: &Option<String>, width: usize) -> String {
+    let description = display_text(description.as_deref().unwrap_or("Unknown"));
     // description
     textwrap::fill(&description, width)
 }
 
-fn format_unit(unit_text: &Option<String>) -> String {
-    let aliases = QUERY_ALIAS.get_or_init(|| QueryAlias::new());
+// Names with Greek letters and bars, without wrapping
+pub(crate) fn display_text(text: &str) -> String {
+    aliases().render_description(text)
+}
+
+// PDG units as they are in the ASCII profile, i.e., "GeV**2" rather than "GeV²"
+pub(crate) fn format_unit(unit_text: &Option<String>) -> String {
+    let aliases = aliases();
     let mut unit = unit_text.clone().unwrap_or(String::from(""));
+    if !aliases.profile.unicode {
+        return unit;
+    }
     for (name, symbol) in aliases.unit_aliases.iter() {
         unit = unit.replace(name, symbol);
     }
@@ -189,31 +416,188 @@ fn format_unit(unit_text: &Option<String>) -> String {
 }
 // Decay print
 pub fn decay_print(decay_channels: &Vec<DecayChannel>) {
-    println!("Related decay(s):");
-    println!("----------------------");
+    print_header("Related decay(s):");
+    let mut table = Table::new(&["Node", "Decay"]).wrap(1);
     for decay in decay_channels {
-        print_decay_channel_info(decay);
+        table.add_row(vec![decay.pdgid.clone(), decay_channel_text(decay)]);
     }
+    table.print();
     println!("----------------------");
     
 }
 
-fn print_decay_channel_info(decay: &DecayChannel) {
+fn decay_channel_text(decay: &DecayChannel) -> String {
     let mut daughter_format = Vec::new();
     for (name, multiplicity) in decay.daughters.iter() {
         daughter_format.push(format!("{}{}", multiplicity, name));
     }
-    let mut text = format!("{} -> {}", decay.parent, daughter_format.join(" + "));
-    text = format_description(&Some(text), 25);
-    let lines: Vec<&str> = text.split('\n').collect();
-    for (i, line) in lines.iter().enumerate() {
-        if i == 0 {
-            println!("{:<70}", line);
-        } else {
-            println!("{:<70}", line);
+    display_text(&format!("{} -> {}", decay.parent, daughter_format.join(" + ")))
+}
+
+// Decay tree print
+pub fn decay_tree_print(tree: &DecayNode) {
+    print_header("Decay tree:");
+    for line in render_decay_tree(tree) {
+        println!("{}", line);
+    }
+    println!("----------------------");
+    if tree.is_leaf() {
+        return;
+    }
+    print_header("Final states by charged multiplicity:");
+    let (states, incomplete): (Vec<FinalState>, Vec<FinalState>) = final_states(tree).into_iter().partition(|state| state.is_complete());
+    for (prongs, fraction) in group_final_states(&states, |state| state.charged_multiplicity) {
+        println!("{:<3} prong(s)   {}", prongs, format_measured(&fraction.fraction));
+    }
+    // paths through particles the depth or probability limit did not decay
+    if !incomplete.is_empty() {
+        println!("incomplete     {}", format_measured(&inclusive_fraction(&incomplete, |_| true).fraction));
+    }
+    println!("----------------------");
+}
+
+fn render_decay_tree(tree: &DecayNode) -> Vec<String> {
+    let mut lines = vec![format_description(&Some(tree.particle.clone()), 200)];
+    render_channels(tree, "", &mut lines);
+    lines
+}
+
+// Each channel is a branch listing all daughters, unstable daughters get their own subtree below it
+fn render_channels(node: &DecayNode, prefix: &str, lines: &mut Vec<String>) {
+    let profile = output_profile();
+    for (i, channel) in node.channels.iter().enumerate() {
+        let is_last = i == node.channels.len() - 1;
+        let daughters = channel.daughters
+            .iter()
+            .map(|daughter| daughter.particle.clone())
+            .collect::<Vec<String>>()
+            .join(" ");
+        lines.push(format!(
+            "{}{}{:.3}% {} (path {:.2e})",
+            prefix,
+            profile.branch(is_last),
+            channel.branching_fraction * 100.0,
+            format_description(&Some(daughters), 200),
+            node.probability * channel.branching_fraction,
+        ));
+
+        let channel_prefix = format!("{}{}", prefix, profile.indent(is_last));
+        let unstable = channel.daughters.iter().filter(|daughter| !daughter.is_stable()).collect::<Vec<_>>();
+        for (j, daughter) in unstable.iter().enumerate() {
+            let is_last_daughter = j == unstable.len() - 1;
+            lines.push(format!(
+                "{}{}{}{}",
+                channel_prefix,
+                profile.branch(is_last_daughter),
+                format_description(&Some(daughter.particle.clone()), 200),
+                if daughter.truncated { " (not decayed, limit reached)" } else { "" },
+            ));
+            let daughter_prefix = format!("{}{}", channel_prefix, profile.indent(is_last_daughter));
+            render_channels(daughter, &daughter_prefix, lines);
         }
     }
-    
+}
+
+// Conservation check print
+pub fn conservation_print(decay: &str, report: &ConservationReport) {
+    print_header(&format!("Conservation check: {}", format_description(&Some(decay.to_string()), 80)));
+    println!("{:<22} {:<10} {:<10} {:<12}", "Quantity", "Initial", "Final", "Status");
+    println!("{}", "-".repeat(60));
+    for check in report.checks.iter() {
+        let status = match (check.verdict, check.weak_allowed) {
+            (Verdict::Conserved, _) => "conserved",
+            (Verdict::Violated, true) => "violated (weak only)",
+            (Verdict::Violated, false) => "VIOLATED",
+            (Verdict::Undetermined, _) => "undetermined",
+        };
+        println!(
+            "{:<22} {:<10} {:<10} {:<12}",
+            check.quantity,
+            check.initial.map_or("?".to_string(), |x| x.to_string()),
+            check.final_state.map_or("?".to_string(), |x| x.to_string()),
+            status,
+        );
+    }
+    println!("{}", "-".repeat(60));
+    let kinematics = match report.kinematically_allowed {
+        Some(true) => "allowed",
+        Some(false) => "FORBIDDEN",
+        None => "undetermined",
+    };
+    println!(
+        "Kinematics     : {} (parent mass {} GeV, sum of daughter masses {} GeV)",
+        kinematics,
+        report.parent_mass.map_or("?".to_string(), |m| format!("{:.6}", m)),
+        report.daughter_mass_sum.map_or("?".to_string(), |m| format!("{:.6}", m)),
+    );
+    println!("Verdict        : {}", if report.is_allowed() { "allowed" } else { "forbidden" });
+    println!("----------------------");
+}
+
+fn format_pdg_value(measured: Option<&Measured>) -> String{
+    measured.map_or("?".to_string(), format_measured)
+}
+
+fn format_pull(pull: Option<f64>) -> String{
+    pull.map_or("-".to_string(), |pull| format!("{:+.1}{}", pull, output_profile().sigma()))
+}
+
+// width is the SLHA width, pdg_width the PDG width with its errors
+pub fn slha_comparison_print(parent: &str, width: f64, pdg_width: Option<Measured>, comparisons: &[ChannelComparison]) {
+    print_header(&format!("SLHA comparison: {}", format_description(&Some(parent.to_string()), 80)));
+    println!(
+        "Width          : {:.4e} GeV (PDG {} GeV, {})",
+        width,
+        format_pdg_value(pdg_width.as_ref()),
+        format_pull(pdg_width.and_then(|pdg_width| pdg_width.pull(width))),
+    );
+    let mut table = Table::new(&["Channel", "SLHA", "PDG", "Pull"]).wrap(0);
+    for comparison in comparisons.iter() {
+        let pdg = match comparison.pdg.as_ref() {
+            Some(mode) if mode.is_measurement => format_pdg_value(mode.fraction.as_ref()),
+            Some(mode) => format!("{} (not a measurement)", mode.node_id),
+            None => "not in PDG".to_string(),
+        };
+        table.add_row(vec![
+            display_text(&comparison.label),
+            comparison.file_value.map_or("missing".to_string(), |value| format!("{:.4e}", value)),
+            pdg,
+            format_pull(comparison.pull),
+        ]);
+    }
+    table.print();
+    println!("----------------------");
+}
+
+fn validation_status(comparison: &ChannelComparison, max_pull: f64) -> &'static str{
+    match (&comparison.pdg, comparison.file_value) {
+        (None, _) => "NOT IN PDG",
+        (_, None) => "MISSING",
+        (Some(mode), _) if !mode.is_measurement => "limit",
+        _ if is_failure(comparison, max_pull) => "PULL",
+        _ => "ok",
+    }
+}
+
+pub fn validation_print(parent: &str, comparisons: &[ChannelComparison], max_pull: f64) {
+    print_header(&format!("Validation: {} (max |pull| {})", format_description(&Some(parent.to_string()), 80), max_pull));
+    let mut table = Table::new(&["Channel", "File", "PDG", "Pull", "Status"]).wrap(0);
+    for comparison in comparisons.iter() {
+        let pdg = match comparison.pdg.as_ref() {
+            Some(mode) if mode.is_measurement => format_pdg_value(mode.fraction.as_ref()),
+            Some(mode) => mode.node_id.clone(),
+            None => "-".to_string(),
+        };
+        table.add_row(vec![
+            display_text(&comparison.label),
+            comparison.file_value.map_or("-".to_string(), |value| format!("{:.4e}", value)),
+            pdg,
+            format_pull(comparison.pull),
+            validation_status(comparison, max_pull).to_string(),
+        ]);
+    }
+    table.print();
+    println!("----------------------");
 }
 
 #[cfg(test)]
@@ -229,4 +613,135 @@ mod test {
         single_particle_print(&muon);
     }
 
+    #[test]
+    fn test_format_pdg_value(){
+        assert_eq!(format_pdg_value(Some(&Measured::new(0.0395, 0.0003, 0.0003))), "(0.03950 ± 0.00030)");
+        assert_eq!(format_pdg_value(Some(&Measured::new(0.0395, 0.0003, 0.0002))), "(0.03950 +0.00030 -0.00020)");
+        assert_eq!(format_pdg_value(Some(&Measured::new(2.1969811e-6, 2.2e-12, 2.2e-12))), "(2.1969811 ± 0.0000022) × E-6");
+        assert_eq!(format_pdg_value(Some(&Measured::upper_limit(4.2e-13, Some(0.9)))), "< 4.2 × E-13 CL=90%");
+        assert_eq!(format_pdg_value(Some(&Measured::range(0.5, 1.25))), "0.5 to 1.3");
+        assert_eq!(format_pdg_value(None), "?");
+        assert_eq!(format_pull(Some(2.04)), "+2.0σ");
+    }
+
+    #[test]
+    fn test_measurement_in_units(){
+        let width = ParticleMeasurement{
+            display_value: Some("2.265".to_string()),
+            display_power_of_ten: Some(-12),
+            plus_error: Some(0.004e-12),
+            minus_error: Some(0.004e-12),
+            ..ParticleMeasurement::test("S035W", "G", 2.265e-12, "GeV")
+        };
+        let units = [Unit::parse("MeV").unwrap(), Unit::parse("fs").unwrap()];
+        let in_mev = measurement_in_units(&width, &units);
+        assert_eq!(in_mev.unit_text.as_deref(), Some("MeV"));
+        assert!((in_mev.value.unwrap() - 2.265e-9).abs() < 1e-15);
+        assert_eq!(in_mev.display_value, None);
+        // ħ/Γ of the tau is about 290 fs
+        let lifetime = measurement_in_units(&width, &units[1..]);
+        assert_eq!(lifetime.unit_text.as_deref(), Some("fs"));
+        assert!((lifetime.value.unwrap() - 290.6).abs() < 0.1);
+        let limit = ParticleMeasurement{ limit_type: Some("U".to_string()), ..width.clone() };
+        assert_eq!(measurement_in_units(&limit, &units[1..]).limit_type.as_deref(), Some("L"));
+        // a mass is not a time
+        let mass = ParticleMeasurement::test("S035M", "M", 1.77686, "GeV");
+        assert_eq!(measurement_in_units(&mass, &units[1..]).unit_text.as_deref(), Some("GeV"));
+
+        // back from a lifetime of 290.3 fs, or a c tau of 87.03 um, to a width of 2.267e-9 MeV
+        let lifetime = ParticleMeasurement{ limit_type: Some("L".to_string()), ..ParticleMeasurement::test("S035T", "T", 290.3e-15, "s") };
+        let width = measurement_in_units(&lifetime, &units[..1]);
+        assert_eq!(width.unit_text.as_deref(), Some("MeV"));
+        assert!((width.value.unwrap() - 2.267e-9).abs() < 1e-12);
+        assert_eq!(width.limit_type.as_deref(), Some("U"));
+        let ctau = ParticleMeasurement::test("S035T", "T", 87.03, "um");
+        assert!((measurement_in_units(&ctau, &units[..1]).value.unwrap() - 2.267e-9).abs() < 1e-12);
+
+        // a width of (2 +1 -3) MeV reaches zero, the lifetime is only bounded below by ħ/3 MeV
+        let width = ParticleMeasurement{ plus_error: Some(1.0), minus_error: Some(3.0), ..ParticleMeasurement::test("S035W", "G", 2.0, "MeV") };
+        let lifetime = measurement_in_units(&width, &units[1..]);
+        assert_eq!(lifetime.limit_type.as_deref(), Some("L"));
+        assert_eq!((lifetime.plus_error, lifetime.minus_error), (None, None));
+        assert!((lifetime.value.unwrap() - 2.194e-7).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_measurement_sections(){
+        let width = ParticleMeasurement::test("S035W", "G", 2.265e-12, "GeV");
+        let moment = ParticleMeasurement{
+            kind: DataType::Other{ code: "MM".to_string(), description: Some("Magnetic moment".to_string()) },
+            ..ParticleMeasurement::test("S035MM", "MM", 1.0, "")
+        };
+        let mass = ParticleMeasurement::test("S035M", "M", 1.77686, "GeV");
+        let measurements = [moment, width.clone(), mass.clone(), mass];
+        let sections = measurement_sections(&measurements);
+        let titles = sections.iter().map(|(title, members)| (title.as_str(), members.len())).collect::<Vec<(&str, usize)>>();
+        assert_eq!(titles, vec![("Mass", 2), ("Width", 1), ("Magnetic moment", 1)]);
+    }
+
+    #[test]
+    fn test_average_check(){
+        let average = WeightedAverage{ average: Measured::new(1776.9, 0.1, 0.1), chi2: 0.5, count: 2, scale_factor: 1.0 };
+        assert_eq!(average_check(&average, Some(&Measured::new(1776.91, 0.12, 0.12))), "consistent, 0.08 of the quoted error apart");
+        assert!(average_check(&average, Some(&Measured::new(1776.5, 0.1, 0.1))).starts_with("differs by 4.00"));
+        assert_eq!(average_check(&average, Some(&Measured::upper_limit(1800.0, None))), "no quoted central value to compare with");
+        assert_eq!(average_check(&average, None), "no quoted central value to compare with");
+    }
+
+    #[test]
+    fn test_footnote_markers(){
+        use crate::pdgdb::footnotes::Footnote;
+        let footnote = |id: i64, text: &str| Footnote{ id, node_id: "S035M".to_string(), index: None, text: text.to_string() };
+        let mut particle = Particle::test_muon();
+        particle.footnotes = vec![footnote(5, "Assumes CPT invariance."), footnote(7, "From a fit.")];
+        let mut markers = FootnoteMarkers::default();
+        assert_eq!(markers.mark("width".to_string(), &[]), "width");
+        assert_eq!(markers.mark("mass".to_string(), &[7]), "mass [1]");
+        assert_eq!(markers.mark("mass difference".to_string(), &[5, 7]), "mass difference [2,1]");
+        assert_eq!(markers.lines(&particle, 100), vec!["[1] From a fit.", "[2] Assumes CPT invariance."]);
+        assert_eq!(markers.lines(&particle, 20)[1], "[2] Assumes CPT\n    invariance.");
+        particle.footnotes.clear();
+        assert!(markers.lines(&particle, 100).is_empty());
+    }
+
+    #[test]
+    fn test_is_large_error(){
+        assert!(is_large_error(&Measured::new(0.0012, 0.0004, 0.0003)));
+        assert!(!is_large_error(&Measured::new(1.77686, 0.00012, 0.00012)));
+        assert!(!is_large_error(&Measured::new(0.0, 0.1, 0.1)));
+        assert!(!is_large_error(&Measured::upper_limit(4.2e-13, None)));
+    }
+
+    #[test]
+    fn test_render_decay_tree() {
+        use crate::pdgdb::queries::decayTree::DecayBranch;
+        let branch = |node_id: &str, branching_fraction: f64, daughters: Vec<DecayNode>| DecayBranch {
+            node_id: node_id.to_string(),
+            branching_fraction,
+            plus_error: None,
+            minus_error: None,
+            daughters,
+        };
+        let mut d0 = DecayNode::leaf("D0", Some(421), Some(0.0), 0.677);
+        d0.channels.push(branch("S032.1", 0.0395, vec![
+            DecayNode::leaf("K-", Some(-321), Some(-1.0), 0.0267),
+            DecayNode::leaf("pi+", Some(211), Some(1.0), 0.0267),
+        ]));
+        let mut dstar = DecayNode::leaf("D*(2010)+", Some(413), Some(1.0), 1.0);
+        dstar.channels.push(branch("M062.1", 0.677, vec![d0, DecayNode::leaf("pi+", Some(211), Some(1.0), 0.677)]));
+        dstar.channels.push(branch("M062.2", 0.307, vec![
+            DecayNode::leaf("D+", Some(411), Some(1.0), 0.307),
+            DecayNode::leaf("pi0", Some(111), Some(0.0), 0.307),
+        ]));
+
+        let lines = render_decay_tree(&dstar);
+        assert_eq!(lines, vec![
+            "D*(2010)+",
+            "├── 67.700% D0 π+ (path 6.77e-1)",
+            "│   └── D0",
+            "│       └── 3.950% K- π+ (path 2.67e-2)",
+            "└── 30.700% D+ π0 (path 3.07e-1)",
+        ]);
+    }
+
 }
\ No newline at end of file
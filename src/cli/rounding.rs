@@ -0,0 +1,174 @@
+// PDG rounding (Review of Particle Physics, Introduction): the three highest-order digits of the error
+// decide its significant digits, 100 to 354 keep two, 355 to 949 keep one and 950 to 999 round up to 1000
+// and keep two. The value is rounded to the same decimal place as the error.
+
+const DIGITS_WITHOUT_ERROR: i32 = 4;
+// Values below 10^-3 or from 10^5 on are scaled by a power of ten, masses like 5279.66 MeV are not
+const SMALL_ORDER: i32 = -3;
+const LARGE_ORDER: i32 = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RoundedValue{
+    pub value: f64,
+    pub plus_error: Option<f64>,
+    pub minus_error: Option<f64>,
+    pub place: i32, // power of ten of the last significant digit
+}
+
+fn order(x: f64) -> i32{
+    x.abs().log10().floor() as i32
+}
+
+// Power of ten of the last significant digit of an error
+pub fn error_place(error: f64) -> i32{
+    let mut order = order(error);
+    let mut digits = (error / 10f64.powi(order - 2)).round();
+    if digits >= 1000.0 {
+        order += 1;
+        digits = (digits / 10.0).round();
+    }
+    if digits <= 354.0 { order - 1 } else { order }
+}
+
+fn round_at(x: f64, place: i32) -> f64{
+    (x / 10f64.powi(place)).round() * 10f64.powi(place)
+}
+
+impl RoundedValue{
+    // Missing or zero errors count as no error, asymmetric errors are rounded at the place of the smaller one
+    pub fn new(value: f64, plus_error: Option<f64>, minus_error: Option<f64>) -> RoundedValue{
+        let positive = |error: Option<f64>| error.filter(|error| error.is_finite() && *error > 0.0);
+        let (plus_error, minus_error) = (positive(plus_error), positive(minus_error));
+        let smallest = [plus_error, minus_error].into_iter().flatten().fold(f64::INFINITY, f64::min);
+        let place = if smallest.is_finite() {
+            error_place(smallest)
+        } else if value != 0.0 && value.is_finite() {
+            order(value) - DIGITS_WITHOUT_ERROR + 1
+        } else {
+            0
+        };
+        RoundedValue{
+            value: round_at(value, place),
+            plus_error: plus_error.map(|error| round_at(error, place)),
+            minus_error: minus_error.map(|error| round_at(error, place)),
+            place,
+        }
+    }
+
+    pub fn has_errors(&self) -> bool{
+        self.plus_error.is_some() || self.minus_error.is_some()
+    }
+
+    // Both errors round to the same number
+    pub fn is_symmetric(&self) -> bool{
+        match (self.plus_error, self.minus_error) {
+            (Some(plus), Some(minus)) => self.text(plus, 0) == self.text(minus, 0),
+            _ => false,
+        }
+    }
+
+    // The power of ten the printers factor out, zero when the value is near one
+    pub fn exponent(&self) -> i32{
+        let reference = if self.value != 0.0 { self.value } else { self.plus_error.or(self.minus_error).unwrap_or(0.0) };
+        if reference == 0.0 || !reference.is_finite() {
+            return 0;
+        }
+        let order = order(reference);
+        if order <= SMALL_ORDER || order >= LARGE_ORDER { order } else { 0 }
+    }
+
+    // A number at the rounded precision divided by 10^exponent, without errors the trailing zeros go
+    fn text(&self, x: f64, exponent: i32) -> String{
+        let decimals = (exponent - self.place).max(0) as usize;
+        let text = format!("{:.*}", decimals, x / 10f64.powi(exponent));
+        if self.has_errors() || !text.contains('.') {
+            text
+        } else {
+            text.trim_end_matches('0').trim_end_matches('.').to_string()
+        }
+    }
+
+    // Value, plus error and minus error divided by 10^exponent()
+    pub fn scaled(&self) -> (String, Option<String>, Option<String>){
+        let exponent = self.exponent();
+        (
+            self.text(self.value, exponent),
+            self.plus_error.map(|error| self.text(error, exponent)),
+            self.minus_error.map(|error| self.text(error, exponent)),
+        )
+    }
+
+    // Lower and upper end of a range given as value, plus and minus error, unscaled
+    pub fn range(&self) -> (String, String){
+        let low = self.value - self.minus_error.unwrap_or(0.0);
+        let high = self.value + self.plus_error.unwrap_or(0.0);
+        (self.text(low, 0), self.text(high, 0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scaled(value: f64, plus: f64, minus: f64) -> (String, Option<String>, Option<String>){
+        RoundedValue::new(value, Some(plus), Some(minus)).scaled()
+    }
+
+    #[test]
+    fn test_error_place(){
+        assert_eq!(error_place(0.119), -2);
+        assert_eq!(error_place(0.367), -1);
+        assert_eq!(error_place(0.0987), -2);
+        assert_eq!(error_place(0.00012), -5);
+        assert_eq!(error_place(0.001), -4);
+        assert_eq!(error_place(35.0), 0);
+        assert_eq!(error_place(460.0), 2);
+    }
+
+    #[test]
+    fn test_published_examples(){
+        // the examples of the PDG introduction
+        let (value, plus, _) = scaled(0.827, 0.119, 0.119);
+        assert_eq!((value.as_str(), plus.as_deref()), ("0.83", Some("0.12")));
+        let (value, plus, _) = scaled(0.827, 0.367, 0.367);
+        assert_eq!((value.as_str(), plus.as_deref()), ("0.8", Some("0.4")));
+        // 950 to 999 round up to 1000 with two digits
+        let (value, plus, _) = scaled(1.2345, 0.0987, 0.0987);
+        assert_eq!((value.as_str(), plus.as_deref()), ("1.23", Some("0.10")));
+        // tau mass 1776.86 +- 0.12 MeV and muon mass 105.6583755 +- 0.0000023 MeV
+        assert_eq!(scaled(1776.86, 0.12, 0.12).0, "1776.86");
+        assert_eq!(scaled(105.6583755, 0.0000023, 0.0000023).0, "105.6583755");
+    }
+
+    #[test]
+    fn test_asymmetric_and_scaled(){
+        let rounded = RoundedValue::new(0.0389, Some(0.0004), Some(0.0003));
+        assert!(!rounded.is_symmetric());
+        assert_eq!(rounded.scaled(), ("0.03890".to_string(), Some("0.00040".to_string()), Some("0.00030".to_string())));
+        // muon lifetime (2.1969811 +- 0.0000022) x 10^-6 s
+        let rounded = RoundedValue::new(2.1969811e-6, Some(2.2e-12), Some(2.2e-12));
+        assert!(rounded.is_symmetric());
+        assert_eq!(rounded.exponent(), -6);
+        assert_eq!(rounded.scaled().0, "2.1969811");
+        // errors above one round the integer digits
+        assert_eq!(scaled(1234.5, 460.0, 460.0), ("1200".to_string(), Some("500".to_string()), Some("500".to_string())));
+        assert_eq!(scaled(123456.0, 460.0, 460.0), ("1.235".to_string(), Some("0.005".to_string()), Some("0.005".to_string())));
+        assert_eq!(scaled(234.5, 46.0, 46.0).0, "230");
+    }
+
+    #[test]
+    fn test_without_errors(){
+        let limit = RoundedValue::new(4.2e-13, None, Some(0.0));
+        assert!(!limit.has_errors());
+        assert_eq!(limit.exponent(), -13);
+        assert_eq!(limit.scaled().0, "4.2");
+        assert_eq!(RoundedValue::new(0.0, None, None).scaled().0, "0");
+        assert_eq!(RoundedValue::new(0.12346, None, None).scaled().0, "0.1235");
+    }
+
+    #[test]
+    fn test_range(){
+        let range = RoundedValue::new(1.0, Some(0.25), Some(0.5));
+        assert_eq!(range.range(), ("0.50".to_string(), "1.25".to_string()));
+    }
+}
@@ -0,0 +1,175 @@
+use textwrap::core::display_width;
+use crate::cli::printAlias::Style;
+use crate::cli::printer::output_profile;
+
+const DEFAULT_WIDTH: usize = 120;
+const MIN_WRAP_WIDTH: usize = 12;
+
+// COLUMNS overrides the width, otherwise the size of the terminal on stdout, and 120 when piped.
+// Shells set COLUMNS without exporting it, so it is usually unset here
+pub fn terminal_width() -> usize{
+    std::env::var("COLUMNS")
+        .ok()
+        .and_then(|columns| columns.trim().parse::<usize>().ok())
+        .filter(|columns| *columns > 0)
+        .or_else(|| terminal_size::terminal_size().map(|(terminal_size::Width(width), _)| width as usize))
+        .unwrap_or(DEFAULT_WIDTH)
+}
+
+// Pads by display columns, combining characters like the bar of ν̅ take no space and wide characters take two
+pub fn pad(text: &str, width: usize) -> String{
+    let padding = width.saturating_sub(display_width(text));
+    format!("{}{}", text, " ".repeat(padding))
+}
+
+struct Column{
+    header: String,
+    wrap: bool, // only wrapped columns give up width when the table is too wide
+}
+
+pub struct Table{
+    columns: Vec<Column>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Table{
+    pub fn new(headers: &[&str]) -> Table{
+        let columns = headers
+            .iter()
+            .map(|header| Column{ header: header.to_string(), wrap: false })
+            .collect();
+        Table{ columns, rows: Vec::new() }
+    }
+
+    // Lets the text of a column wrap, i.e., descriptions
+    pub fn wrap(mut self, column: usize) -> Table{
+        self.columns[column].wrap = true;
+        self
+    }
+
+    pub fn add_row(&mut self, row: Vec<String>){
+        self.rows.push(row);
+    }
+
+    // Widest line of every column, the header included
+    fn natural_widths(&self) -> Vec<usize>{
+        self.columns
+            .iter()
+            .enumerate()
+            .map(|(i, column)| {
+                self.rows
+                    .iter()
+                    .filter_map(|row| row.get(i))
+                    .flat_map(|cell| cell.lines())
+                    .map(display_width)
+                    .chain(std::iter::once(display_width(&column.header)))
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect()
+    }
+
+    // Shrinks the widest wrapped column first until the table fits, no column goes below MIN_WRAP_WIDTH
+    pub fn widths(&self, total_width: usize) -> Vec<usize>{
+        let mut widths = self.natural_widths();
+        let separators = self.columns.len().saturating_sub(1);
+        while widths.iter().sum::<usize>() + separators > total_width {
+            let widest = widths
+                .iter()
+                .enumerate()
+                .filter(|(i, width)| self.columns[*i].wrap && **width > MIN_WRAP_WIDTH)
+                .max_by_key(|(_, width)| **width)
+                .map(|(i, _)| i);
+            match widest {
+                Some(i) => widths[i] -= 1,
+                None => break,
+            }
+        }
+        widths
+    }
+
+    fn render_row(&self, cells: &[String], widths: &[usize]) -> Vec<String>{
+        let wrapped = self.columns
+            .iter()
+            .zip(widths.iter())
+            .enumerate()
+            .map(|(i, (column, width))| {
+                let cell = cells.get(i).map_or("", |cell| cell.as_str());
+                if column.wrap {
+                    textwrap::wrap(cell, *width).into_iter().map(|line| line.into_owned()).collect::<Vec<String>>()
+                } else {
+                    cell.lines().map(|line| line.to_string()).collect::<Vec<String>>()
+                }
+            })
+            .collect::<Vec<Vec<String>>>();
+        let height = wrapped.iter().map(|lines| lines.len()).max().unwrap_or(0).max(1);
+        (0..height)
+            .map(|line| {
+                let text = wrapped
+                    .iter()
+                    .zip(widths.iter())
+                    .map(|(lines, width)| pad(lines.get(line).map_or("", |text| text.as_str()), *width))
+                    .collect::<Vec<String>>()
+                    .join(" ");
+                text.trim_end().to_string()
+            })
+            .collect()
+    }
+
+    pub fn render(&self, total_width: usize) -> Vec<String>{
+        let widths = self.widths(total_width);
+        let headers = self.columns.iter().map(|column| column.header.clone()).collect::<Vec<String>>();
+        let profile = output_profile();
+        let mut lines = self.render_row(&headers, &widths)
+            .into_iter()
+            .map(|line| profile.paint(&line, Style::Header))
+            .collect::<Vec<String>>();
+        lines.push("-".repeat(widths.iter().sum::<usize>() + widths.len().saturating_sub(1)));
+        for row in self.rows.iter() {
+            lines.extend(self.render_row(row, &widths));
+        }
+        lines
+    }
+
+    pub fn print(&self){
+        for line in self.render(terminal_width()) {
+            println!("{}", line);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pad_by_display_width(){
+        // "ν̅" is two chars but one column
+        assert_eq!(pad("ν̅e", 4), "ν̅e  ");
+        assert_eq!(pad("K̅0 π+", 8), "K̅0 π+   ");
+        assert_eq!(pad("too long", 3), "too long");
+    }
+
+    #[test]
+    fn test_alignment_with_combining_characters(){
+        let mut table = Table::new(&["Decay", "Value"]).wrap(0);
+        table.add_row(vec!["ν̅e e-".to_string(), "1.0".to_string()]);
+        table.add_row(vec!["pi+ pi-".to_string(), "2.0".to_string()]);
+        let lines = table.render(80);
+        assert_eq!(lines[0], "Decay   Value");
+        assert_eq!(lines[1], "-------------");
+        assert_eq!(lines[2], "ν̅e e-   1.0");
+        assert_eq!(lines[3], "pi+ pi- 2.0");
+    }
+
+    #[test]
+    fn test_wrap_to_terminal_width(){
+        let mut table = Table::new(&["Description", "Value"]).wrap(0);
+        table.add_row(vec!["a long description of a measurement".to_string(), "1.0".to_string()]);
+        let lines = table.render(20);
+        assert_eq!(table.widths(20), vec![14, 5]);
+        assert!(lines.iter().all(|line| display_width(line) <= 20));
+        assert_eq!(lines[2], "a long         1.0");
+        assert_eq!(lines[3], "description of");
+    }
+}
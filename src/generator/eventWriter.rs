@@ -0,0 +1,121 @@
+use crate::generator::sampler::GeneratedEvent;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EventFormat{
+    HepMC3, // HepMC3 ASCII (Asciiv3)
+    Lhe,    // Les Houches Event file, with the decayed particles as intermediate resonances
+}
+
+impl EventFormat{
+    pub fn header(&self) -> String{
+        match self {
+            EventFormat::HepMC3 => "HepMC::Version 3.02.05\nHepMC::Asciiv3-START_EVENT_LISTING".to_string(),
+            EventFormat::Lhe => "<LesHouchesEvents version=\"3.0\">\n<header>\n<!-- generated by pdgQuery from PDG branching fractions -->\n</header>\n<init>\n0 0 0.0 0.0 0 0 0 0 3 1\n1.0 0.0 1.0 1\n</init>".to_string(),
+        }
+    }
+
+    pub fn footer(&self) -> String{
+        match self {
+            EventFormat::HepMC3 => "HepMC::Asciiv3-END_EVENT_LISTING".to_string(),
+            EventFormat::Lhe => "</LesHouchesEvents>".to_string(),
+        }
+    }
+
+    pub fn write_event(&self, index: usize, event: &GeneratedEvent) -> String{
+        match self {
+            EventFormat::HepMC3 => hepmc3_event(index, event),
+            EventFormat::Lhe => lhe_event(event),
+        }
+    }
+}
+
+// Each particle refers to its parent particle, so the decay vertices are implicit and only counted in the E line
+fn hepmc3_event(index: usize, event: &GeneratedEvent) -> String{
+    let vertices = event.particles.iter().filter(|particle| particle.decayed).count();
+    let mut lines = vec![
+        format!("E {} {} {}", index, vertices, event.particles.len()),
+        "U GEV MM".to_string(),
+    ];
+    for (i, particle) in event.particles.iter().enumerate() {
+        lines.push(format!(
+            "P {} {} {} {:.8e} {:.8e} {:.8e} {:.8e} {:.8e} {}",
+            i + 1,
+            particle.parent.map_or(0, |parent| parent + 1),
+            particle.mcid,
+            particle.momentum.px,
+            particle.momentum.py,
+            particle.momentum.pz,
+            particle.momentum.e,
+            particle.mass,
+            if particle.decayed { 2 } else { 1 },
+        ));
+    }
+    lines.join("\n")
+}
+
+fn lhe_event(event: &GeneratedEvent) -> String{
+    let mut lines = vec![
+        "<event>".to_string(),
+        format!("{} 1 1.0 {:.8e} -1.0 -1.0", event.particles.len(), event.particles[0].mass),
+    ];
+    for particle in event.particles.iter() {
+        let mother = particle.parent.map_or(0, |parent| parent + 1);
+        lines.push(format!(
+            "{} {} {} {} 0 0 {:.10e} {:.10e} {:.10e} {:.10e} {:.10e} 0.0 9.0",
+            particle.mcid,
+            if particle.decayed { 2 } else { 1 },
+            mother,
+            mother,
+            particle.momentum.px,
+            particle.momentum.py,
+            particle.momentum.pz,
+            particle.momentum.e,
+            particle.mass,
+        ));
+    }
+    lines.push("</event>".to_string());
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::phaseSpace::FourVector;
+    use crate::generator::sampler::GeneratedParticle;
+
+    fn two_body_event() -> GeneratedEvent{
+        let particle = |name: &str, mcid: i64, mass: f64, momentum: FourVector, parent: Option<usize>, decayed: bool| GeneratedParticle{
+            name: name.to_string(), mcid, mass, momentum, parent, decayed,
+        };
+        GeneratedEvent{
+            particles: vec![
+                particle("D0", 421, 1.86484, FourVector::at_rest(1.86484), None, true),
+                particle("K-", -321, 0.493677, FourVector::new(0.0, 0.0, 0.8612, 0.99265), Some(0), false),
+                particle("pi+", 211, 0.13957, FourVector::new(0.0, 0.0, -0.8612, 0.87220), Some(0), false),
+            ],
+        }
+    }
+
+    #[test]
+    fn test_hepmc3_event(){
+        let text = EventFormat::HepMC3.write_event(7, &two_body_event());
+        let lines = text.lines().collect::<Vec<&str>>();
+        assert_eq!(lines[0], "E 7 1 3");
+        assert_eq!(lines[1], "U GEV MM");
+        assert!(lines[2].starts_with("P 1 0 421 "));
+        assert!(lines[2].ends_with(" 2"));
+        assert!(lines[3].starts_with("P 2 1 -321 "));
+        assert!(lines[4].ends_with(" 1"));
+    }
+
+    #[test]
+    fn test_lhe_event(){
+        let text = EventFormat::Lhe.write_event(0, &two_body_event());
+        let lines = text.lines().collect::<Vec<&str>>();
+        assert_eq!(lines.first(), Some(&"<event>"));
+        assert_eq!(lines.last(), Some(&"</event>"));
+        assert!(lines[1].starts_with("3 1 1.0 "));
+        assert!(lines[2].starts_with("421 2 0 0 0 0 "));
+        assert!(lines[3].starts_with("-321 1 1 1 0 0 "));
+    }
+}
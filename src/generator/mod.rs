@@ -0,0 +1,54 @@
+pub mod sampler;
+pub mod phaseSpace;
+pub mod eventWriter;
+
+// SplitMix64, small and fast with a good enough distribution for toy studies, the same seed gives the same events
+#[derive(Debug, Clone)]
+pub struct Rng{
+    state: u64,
+}
+
+impl Rng{
+    pub fn new(seed: u64) -> Self{
+        Rng{ state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64{
+        self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    // Uniform in [0, 1) using the upper 53 bits
+    pub fn uniform(&mut self) -> f64{
+        (self.next_u64() >> 11) as f64 / (1_u64 << 53) as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rng_reproducible(){
+        let mut first = Rng::new(42);
+        let mut second = Rng::new(42);
+        let mut other = Rng::new(43);
+        let a = (0..5).map(|_| first.next_u64()).collect::<Vec<u64>>();
+        let b = (0..5).map(|_| second.next_u64()).collect::<Vec<u64>>();
+        let c = (0..5).map(|_| other.next_u64()).collect::<Vec<u64>>();
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+    }
+
+    #[test]
+    fn test_rng_uniform_range(){
+        let mut rng = Rng::new(7);
+        let values = (0..10000).map(|_| rng.uniform()).collect::<Vec<f64>>();
+        assert!(values.iter().all(|&x| (0.0..1.0).contains(&x)));
+        let mean = values.iter().sum::<f64>() / values.len() as f64;
+        assert!((mean - 0.5).abs() < 0.01);
+    }
+}
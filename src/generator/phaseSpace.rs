@@ -0,0 +1,219 @@
+use std::f64::consts::PI;
+
+use crate::generator::Rng;
+
+const MAX_ATTEMPTS: usize = 100_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct FourVector{
+    pub px: f64,
+    pub py: f64,
+    pub pz: f64,
+    pub e: f64,
+}
+
+impl FourVector{
+    pub fn new(px: f64, py: f64, pz: f64, e: f64) -> Self{
+        FourVector{ px, py, pz, e }
+    }
+
+    pub fn at_rest(mass: f64) -> Self{
+        FourVector::new(0.0, 0.0, 0.0, mass)
+    }
+
+    pub fn mass(&self) -> f64{
+        let m2 = self.e * self.e - self.px * self.px - self.py * self.py - self.pz * self.pz;
+        m2.max(0.0).sqrt()
+    }
+
+    pub fn add(&self, other: &FourVector) -> FourVector{
+        FourVector::new(self.px + other.px, self.py + other.py, self.pz + other.pz, self.e + other.e)
+    }
+
+    // Velocity of the frame in which this vector is at rest
+    pub fn boost_vector(&self) -> (f64, f64, f64){
+        (self.px / self.e, self.py / self.e, self.pz / self.e)
+    }
+
+    pub fn boost(&self, (bx, by, bz): (f64, f64, f64)) -> FourVector{
+        let b2 = bx * bx + by * by + bz * bz;
+        if b2 == 0.0 {
+            return *self;
+        }
+        let gamma = 1.0 / (1.0 - b2).sqrt();
+        let bp = bx * self.px + by * self.py + bz * self.pz;
+        let gamma2 = (gamma - 1.0) / b2;
+        FourVector::new(
+            self.px + gamma2 * bp * bx + gamma * bx * self.e,
+            self.py + gamma2 * bp * by + gamma * by * self.e,
+            self.pz + gamma2 * bp * bz + gamma * bz * self.e,
+            gamma * (self.e + bp),
+        )
+    }
+
+    // Rotate about the z axis and then about the y axis
+    fn rotate(&self, (cos_z, sin_z): (f64, f64), (cos_y, sin_y): (f64, f64)) -> FourVector{
+        let px = cos_z * self.px - sin_z * self.py;
+        let py = sin_z * self.px + cos_z * self.py;
+        FourVector::new(cos_y * px - sin_y * self.pz, py, sin_y * px + cos_y * self.pz, self.e)
+    }
+}
+
+// Momentum of the daughters in the two-body decay a -> b c
+fn two_body_momentum(a: f64, b: f64, c: f64) -> f64{
+    let x = (a - b - c) * (a + b + c) * (a - b + c) * (a + b - c);
+    if x <= 0.0 { 0.0 } else { x.sqrt() / (2.0 * a) }
+}
+
+// N-body phase space following GENBOD (F. James, CERN program library W515), the same algorithm as ROOT's TGenPhaseSpace.
+// The decay is built as a chain of two-body decays of intermediate invariant masses, weighted by their momenta.
+pub struct PhaseSpace{
+    parent_mass: f64,
+    masses: Vec<f64>,
+    max_weight: f64,
+}
+
+impl PhaseSpace{
+    // None if the decay is kinematically forbidden or has fewer than two daughters
+    pub fn new(parent_mass: f64, masses: &[f64]) -> Option<Self>{
+        let kinetic = parent_mass - masses.iter().sum::<f64>();
+        if masses.len() < 2 || kinetic <= 0.0 {
+            return None;
+        }
+        // the largest weight happens when all the kinetic energy goes to each step in turn
+        let mut em_max = kinetic + masses[0];
+        let mut em_min = 0.0;
+        let mut weight = 1.0;
+        for n in 1..masses.len() {
+            em_min += masses[n - 1];
+            em_max += masses[n];
+            weight *= two_body_momentum(em_max, em_min, masses[n]);
+        }
+        Some(PhaseSpace{
+            parent_mass,
+            masses: masses.to_vec(),
+            max_weight: 1.0 / weight,
+        })
+    }
+
+    // One weighted configuration in the parent rest frame, the weight is normalised to at most one
+    pub fn generate_weighted(&self, rng: &mut Rng) -> (Vec<FourVector>, f64){
+        let n = self.masses.len();
+        let kinetic = self.parent_mass - self.masses.iter().sum::<f64>();
+
+        let mut random = vec![0.0; n];
+        random[n - 1] = 1.0;
+        for r in random.iter_mut().take(n - 1).skip(1) {
+            *r = rng.uniform();
+        }
+        random.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+        let mut sum = 0.0;
+        let invariant_masses = (0..n)
+            .map(|i| {
+                sum += self.masses[i];
+                random[i] * kinetic + sum
+            })
+            .collect::<Vec<f64>>();
+
+        let mut weight = self.max_weight;
+        let momenta = (0..n - 1)
+            .map(|i| {
+                let p = two_body_momentum(invariant_masses[i + 1], invariant_masses[i], self.masses[i + 1]);
+                weight *= p;
+                p
+            })
+            .collect::<Vec<f64>>();
+
+        let energy = |p: f64, m: f64| (p * p + m * m).sqrt();
+        let mut daughters = vec![
+            FourVector::new(0.0, momenta[0], 0.0, energy(momenta[0], self.masses[0])),
+            FourVector::new(0.0, -momenta[0], 0.0, energy(momenta[0], self.masses[1])),
+        ];
+        let mut i = 1;
+        loop {
+            let cos_z = 2.0 * rng.uniform() - 1.0;
+            let angle_y = 2.0 * PI * rng.uniform();
+            let z = (cos_z, (1.0 - cos_z * cos_z).sqrt());
+            let y = (angle_y.cos(), angle_y.sin());
+            for daughter in daughters.iter_mut() {
+                *daughter = daughter.rotate(z, y);
+            }
+            if i == n - 1 {
+                break;
+            }
+            let beta = momenta[i] / energy(momenta[i], invariant_masses[i]);
+            for daughter in daughters.iter_mut() {
+                *daughter = daughter.boost((0.0, beta, 0.0));
+            }
+            i += 1;
+            daughters.push(FourVector::new(0.0, -momenta[i - 1], 0.0, energy(momenta[i - 1], self.masses[i])));
+        }
+        (daughters, weight)
+    }
+
+    // Unweighted configuration by accept-reject on the normalised weight
+    pub fn generate(&self, rng: &mut Rng) -> Vec<FourVector>{
+        let mut last = Vec::new();
+        for _ in 0..MAX_ATTEMPTS {
+            let (daughters, weight) = self.generate_weighted(rng);
+            if rng.uniform() < weight {
+                return daughters;
+            }
+            last = daughters;
+        }
+        last
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn total(momenta: &[FourVector]) -> FourVector{
+        momenta.iter().fold(FourVector::default(), |sum, p| sum.add(p))
+    }
+
+    #[test]
+    fn test_two_body(){
+        let phase_space = PhaseSpace::new(1.86484, &[0.493677, 0.13957]).unwrap();
+        let mut rng = Rng::new(3);
+        let daughters = phase_space.generate(&mut rng);
+        let p = |v: &FourVector| (v.px * v.px + v.py * v.py + v.pz * v.pz).sqrt();
+        assert!((p(&daughters[0]) - 0.8612).abs() < 1e-3);
+        assert!((daughters[0].mass() - 0.493677).abs() < 1e-9);
+        assert!((total(&daughters).e - 1.86484).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_momentum_conservation(){
+        let masses = [0.13957, 0.13957, 0.1349768, 0.0];
+        let phase_space = PhaseSpace::new(1.77686, &masses).unwrap();
+        let mut rng = Rng::new(11);
+        for _ in 0..100 {
+            let (daughters, weight) = phase_space.generate_weighted(&mut rng);
+            assert!(weight > 0.0 && weight <= 1.0 + 1e-12);
+            let sum = total(&daughters);
+            assert!(sum.px.abs() < 1e-9 && sum.py.abs() < 1e-9 && sum.pz.abs() < 1e-9);
+            assert!((sum.e - 1.77686).abs() < 1e-9);
+            for (daughter, mass) in daughters.iter().zip(masses.iter()) {
+                assert!((daughter.mass() - mass).abs() < 1e-6);
+            }
+        }
+    }
+
+    #[test]
+    fn test_boost(){
+        let parent = FourVector::new(0.0, 0.0, 3.0, 5.0);
+        let at_rest = FourVector::at_rest(4.0);
+        let boosted = at_rest.boost(parent.boost_vector());
+        assert!((boosted.pz - 3.0).abs() < 1e-12);
+        assert!((boosted.e - 5.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_forbidden(){
+        assert!(PhaseSpace::new(0.1349768, &[0.10566, 0.10566]).is_none());
+        assert!(PhaseSpace::new(1.0, &[0.1]).is_none());
+    }
+}
@@ -0,0 +1,304 @@
+use std::collections::BTreeMap;
+
+use crate::generator::Rng;
+use crate::generator::phaseSpace::{FourVector, PhaseSpace};
+use crate::pdgdb::queries::decayTree::{DecayBranch, DecayNode};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SampledEvent{
+    pub final_state: Vec<String>,
+    pub modes: Vec<String>, // node_id of every decay mode chosen, in the order they were sampled
+}
+
+impl SampledEvent{
+    pub fn to_json(&self, index: usize, parent: &str) -> String{
+        let quoted = |items: &[String]| items
+            .iter()
+            .map(|item| format!("\"{}\"", json_escape(item)))
+            .collect::<Vec<String>>()
+            .join(",");
+        format!(
+            "{{\"event\":{},\"parent\":\"{}\",\"final_state\":[{}],\"modes\":[{}]}}",
+            index,
+            json_escape(parent),
+            quoted(&self.final_state),
+            quoted(&self.modes),
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GeneratedParticle{
+    pub name: String,
+    pub mcid: i64, // 0 when the particle has no MC ID
+    pub mass: f64,
+    pub momentum: FourVector,
+    pub parent: Option<usize>, // index into GeneratedEvent.particles
+    pub decayed: bool,
+}
+
+// Particles in the order they were produced, the first one is the parent at rest
+#[derive(Debug, Clone, Default)]
+pub struct GeneratedEvent{
+    pub particles: Vec<GeneratedParticle>,
+}
+
+pub fn json_escape(text: &str) -> String{
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+// Draws per event before generate_open gives up, a closed channel only wastes a draw
+const MAX_ATTEMPTS: usize = 1000;
+
+// Picks decay channels of an expanded tree according to their branching fractions.
+// Only the modes in the tree take part, which already excludes upper limits and inclusive modes,
+// the missing fraction of each particle is renormalised away and reported by unassigned_fractions.
+pub struct DecaySampler<'a>{
+    tree: &'a DecayNode,
+    rng: Rng,
+}
+
+impl<'a> DecaySampler<'a>{
+    pub fn new(tree: &'a DecayNode, seed: u64) -> Self{
+        DecaySampler{
+            tree,
+            rng: Rng::new(seed),
+        }
+    }
+
+    pub fn sample(&mut self) -> SampledEvent{
+        let mut event = SampledEvent{ final_state: Vec::new(), modes: Vec::new() };
+        DecaySampler::sample_node(self.tree, &mut self.rng, &mut event);
+        event
+    }
+
+    fn sample_node(node: &DecayNode, rng: &mut Rng, event: &mut SampledEvent){
+        if node.is_leaf() {
+            event.final_state.push(node.particle.clone());
+            return;
+        }
+        let chosen = DecaySampler::choose_channel(node, rng);
+        event.modes.push(chosen.node_id.clone());
+        for daughter in chosen.daughters.iter() {
+            DecaySampler::sample_node(daughter, rng, event);
+        }
+    }
+
+    fn choose_channel<'b>(node: &'b DecayNode, rng: &mut Rng) -> &'b DecayBranch{
+        let total: f64 = node.channels.iter().map(|channel| channel.branching_fraction).sum();
+        let mut target = rng.uniform() * total;
+        // falls back to the last channel if rounding leaves a tiny remainder
+        let mut chosen = node.channels.last().unwrap();
+        for channel in node.channels.iter() {
+            if target < channel.branching_fraction {
+                chosen = channel;
+                break;
+            }
+            target -= channel.branching_fraction;
+        }
+        chosen
+    }
+
+    // Like sample but with four-momenta from n-body phase space, every decay is generated in the rest frame
+    // of its parent and boosted into the frame of the root, which is at rest.
+    // Fails when a mass is missing or a chosen channel is kinematically closed at the nominal masses.
+    pub fn generate(&mut self) -> Result<GeneratedEvent, String>{
+        let mass = self.tree.mass.ok_or(format!("No mass for {}", self.tree.particle))?;
+        let mut event = GeneratedEvent::default();
+        DecaySampler::generate_node(self.tree, FourVector::at_rest(mass), None, &mut self.rng, &mut event)?;
+        Ok(event)
+    }
+
+    fn generate_node(node: &DecayNode, momentum: FourVector, parent: Option<usize>, rng: &mut Rng, event: &mut GeneratedEvent) -> Result<(), String>{
+        let index = event.particles.len();
+        event.particles.push(GeneratedParticle{
+            name: node.particle.clone(),
+            mcid: node.mcid.unwrap_or(0),
+            mass: node.mass.unwrap_or(momentum.mass()),
+            momentum,
+            parent,
+            decayed: !node.is_leaf(),
+        });
+        if node.is_leaf() {
+            return Ok(());
+        }
+        let chosen = DecaySampler::choose_channel(node, rng);
+        let masses = chosen.daughters
+            .iter()
+            .map(|daughter| daughter.mass.ok_or(format!("No mass for {}", daughter.particle)))
+            .collect::<Result<Vec<f64>, String>>()?;
+        let phase_space = PhaseSpace::new(momentum.mass(), &masses)
+            .ok_or(format!("{} is closed for {}", chosen.node_id, node.particle))?;
+        let boost = momentum.boost_vector();
+        for (daughter, rest_frame) in chosen.daughters.iter().zip(phase_space.generate(rng)) {
+            DecaySampler::generate_node(daughter, rest_frame.boost(boost), Some(index), rng, event)?;
+        }
+        Ok(())
+    }
+
+    // Draws again when a chosen channel is closed, so every call that succeeds gives an event. Fails at once when
+    // no channel of the root is open at the nominal masses, and after MAX_ATTEMPTS draws otherwise
+    pub fn generate_open(&mut self) -> Result<GeneratedEvent, String>{
+        let mass = self.tree.mass.ok_or(format!("No mass for {}", self.tree.particle))?;
+        let is_open = |channel: &DecayBranch| channel.daughters
+            .iter()
+            .map(|daughter| daughter.mass)
+            .sum::<Option<f64>>()
+            .is_some_and(|daughter_mass| daughter_mass < mass);
+        if !self.tree.channels.iter().any(is_open) {
+            return Err(format!("No open decay channel of {}", self.tree.particle));
+        }
+        let mut last_error = String::new();
+        for _ in 0..MAX_ATTEMPTS {
+            match self.generate() {
+                Ok(event) => return Ok(event),
+                Err(error) => last_error = error,
+            }
+        }
+        Err(format!("No event in {} draws, the last failed with: {}", MAX_ATTEMPTS, last_error))
+    }
+
+    // 1 - sum of the sampled branching fractions for every particle that gets decayed
+    pub fn unassigned_fractions(&self) -> BTreeMap<String, f64>{
+        let mut fractions = BTreeMap::new();
+        DecaySampler::collect_unassigned(self.tree, &mut fractions);
+        fractions
+    }
+
+    fn collect_unassigned(node: &DecayNode, fractions: &mut BTreeMap<String, f64>){
+        if node.is_leaf() {
+            return;
+        }
+        let total: f64 = node.channels.iter().map(|channel| channel.branching_fraction).sum();
+        fractions.insert(node.particle.clone(), (1.0 - total).max(0.0));
+        for daughter in node.channels.iter().flat_map(|channel| channel.daughters.iter()) {
+            DecaySampler::collect_unassigned(daughter, fractions);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn branch(node_id: &str, branching_fraction: f64, daughters: Vec<DecayNode>) -> DecayBranch{
+        DecayBranch{
+            node_id: node_id.to_string(),
+            branching_fraction,
+            plus_error: None,
+            minus_error: None,
+            daughters,
+        }
+    }
+
+    fn leaf(name: &str) -> DecayNode{
+        DecayNode::leaf(name, None, None, 0.0)
+    }
+
+    fn tau_tree() -> DecayNode{
+        let mut tau = leaf("tau-");
+        tau.channels.push(branch("S035.1", 0.17, vec![leaf("mu-"), leaf("nubar_mu"), leaf("nu_tau")]));
+        tau.channels.push(branch("S035.2", 0.18, vec![leaf("e-"), leaf("nubar_e"), leaf("nu_tau")]));
+        tau.channels.push(branch("S035.3", 0.25, vec![leaf("pi-"), leaf("pi0"), leaf("nu_tau")]));
+        tau
+    }
+
+    #[test]
+    fn test_channel_frequencies(){
+        let tree = tau_tree();
+        let mut sampler = DecaySampler::new(&tree, 2024);
+        let events = (0..20000).map(|_| sampler.sample()).collect::<Vec<_>>();
+        let frequency = |mode: &str| events.iter().filter(|event| event.modes[0] == mode).count() as f64 / events.len() as f64;
+        // renormalised to the listed 0.6
+        assert!((frequency("S035.1") - 0.17 / 0.6).abs() < 0.015);
+        assert!((frequency("S035.3") - 0.25 / 0.6).abs() < 0.015);
+        assert_eq!(events[0].final_state.len(), 3);
+    }
+
+    #[test]
+    fn test_seeded_and_recursive(){
+        let mut pi0 = leaf("pi0");
+        pi0.channels.push(branch("S009.1", 0.988, vec![leaf("gamma"), leaf("gamma")]));
+        let mut tree = tau_tree();
+        tree.channels = vec![branch("S035.3", 0.25, vec![leaf("pi-"), pi0, leaf("nu_tau")])];
+
+        let first = DecaySampler::new(&tree, 1).sample();
+        let second = DecaySampler::new(&tree, 1).sample();
+        assert_eq!(first, second);
+        assert_eq!(first.modes, vec!["S035.3", "S009.1"]);
+        assert_eq!(first.final_state, vec!["pi-", "gamma", "gamma", "nu_tau"]);
+
+        let unassigned = DecaySampler::new(&tree, 1).unassigned_fractions();
+        assert!((unassigned["tau-"] - 0.75).abs() < 1e-12);
+        assert!((unassigned["pi0"] - 0.012).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_generate_momenta(){
+        let mass = |mut node: DecayNode, mass: f64| { node.mass = Some(mass); node };
+        let pi0 = mass(leaf("pi0"), 0.1349768);
+        let mut pi0_decaying = pi0.clone();
+        pi0_decaying.channels.push(branch("S009.1", 0.988, vec![mass(leaf("gamma"), 0.0), mass(leaf("gamma"), 0.0)]));
+        let mut tau = mass(leaf("tau-"), 1.77686);
+        tau.channels.push(branch("S035.3", 0.25, vec![mass(leaf("pi-"), 0.13957), pi0_decaying, mass(leaf("nu_tau"), 0.0)]));
+
+        let mut sampler = DecaySampler::new(&tau, 5);
+        let event = sampler.generate().unwrap();
+        let names = event.particles.iter().map(|particle| particle.name.as_str()).collect::<Vec<&str>>();
+        assert_eq!(names, vec!["tau-", "pi-", "pi0", "gamma", "gamma", "nu_tau"]);
+        assert_eq!(event.particles[3].parent, Some(2));
+        assert!(event.particles[2].decayed);
+
+        let final_sum = event.particles
+            .iter()
+            .filter(|particle| !particle.decayed)
+            .fold(FourVector::default(), |sum, particle| sum.add(&particle.momentum));
+        assert!((final_sum.e - 1.77686).abs() < 1e-9);
+        assert!(final_sum.px.abs() < 1e-9 && final_sum.py.abs() < 1e-9 && final_sum.pz.abs() < 1e-9);
+
+        tau.mass = None;
+        assert!(DecaySampler::new(&tau, 5).generate().is_err());
+    }
+
+    #[test]
+    fn test_generate_open(){
+        let mass = |mut node: DecayNode, mass: f64| { node.mass = Some(mass); node };
+        // K- K+ is closed for a D0 lighter than two kaons
+        let mut d0 = mass(leaf("D0"), 0.9);
+        d0.channels.push(branch("S032.1", 0.5, vec![mass(leaf("K-"), 0.493677), mass(leaf("K+"), 0.493677)]));
+        d0.channels.push(branch("S032.2", 0.5, vec![mass(leaf("pi-"), 0.13957), mass(leaf("pi+"), 0.13957)]));
+        let mut sampler = DecaySampler::new(&d0, 3);
+        assert!((0..100).any(|_| sampler.generate().is_err()));
+        for _ in 0..100 {
+            let event = sampler.generate_open().unwrap();
+            assert_eq!(event.particles[1].name, "pi-");
+        }
+
+        d0.channels.remove(1);
+        let error = DecaySampler::new(&d0, 3).generate_open().unwrap_err();
+        assert_eq!(error, "No open decay channel of D0");
+    }
+
+    #[test]
+    fn test_json_line(){
+        let event = SampledEvent{
+            final_state: vec!["K-".to_string(), "pi+".to_string()],
+            modes: vec!["S032.1".to_string()],
+        };
+        assert_eq!(
+            event.to_json(3, "D0"),
+            r#"{"event":3,"parent":"D0","final_state":["K-","pi+"],"modes":["S032.1"]}"#
+        );
+        assert_eq!(json_escape("a\"b\\c"), "a\\\"b\\\\c");
+    }
+}
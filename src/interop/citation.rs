@@ -0,0 +1,175 @@
+// BibTeX and RIS entries for the PDG edition and the papers behind a value. The PDG gives the first author only
+// in the document id, so the other authors are "others" and the journal is the PDG abbreviation. The edition is
+// cited as a whole with its citation text as a note, which names the journal of the year.
+use crate::pdgdb::references::{Edition, Reference};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CitationFormat{
+    BibTex,
+    Ris,
+}
+
+const INSPIRE_URL: &str = "https://inspirehep.net/literature/";
+
+// Letters and digits of the document id, "ABLIKIM 2014D" becomes "ABLIKIM2014D"
+fn bibtex_key(reference: &Reference) -> String{
+    let key = reference.document_id
+        .as_deref()
+        .unwrap_or("")
+        .chars()
+        .filter(|letter| letter.is_ascii_alphanumeric())
+        .collect::<String>();
+    if key.is_empty() { format!("PDGReference{}", reference.id) } else { key }
+}
+
+// The characters BibTeX or LaTeX would read as markup
+fn bibtex_escape(text: &str) -> String{
+    text.chars()
+        .map(|c| match c {
+            '\\' => "\\textbackslash{}".to_string(),
+            '&' | '%' | '#' | '$' | '_' | '{' | '}' => format!("\\{}", c),
+            '^' => "\\textasciicircum{}".to_string(),
+            '~' => "\\textasciitilde{}".to_string(),
+            c => c.to_string(),
+        })
+        .collect()
+}
+
+fn bibtex_entry(kind: &str, key: &str, fields: &[(&str, Option<String>)]) -> String{
+    let fields = fields
+        .iter()
+        .filter_map(|(name, value)| Some(format!("  {} = {{{}}},", name, value.as_ref()?)))
+        .collect::<Vec<String>>();
+    format!("@{}{{{},\n{}\n}}", kind, key, fields.join("\n"))
+}
+
+fn ris_entry(kind: &str, fields: &[(&str, Option<String>)]) -> String{
+    let mut lines = vec![format!("TY  - {}", kind)];
+    lines.extend(fields.iter().filter_map(|(tag, value)| Some(format!("{}  - {}", tag, value.as_ref()?))));
+    lines.push("ER  - ".to_string());
+    lines.join("\n")
+}
+
+fn edition_entry(edition: &Edition, format: CitationFormat) -> String{
+    let title = Some("Review of Particle Physics".to_string());
+    match format {
+        CitationFormat::BibTex => bibtex_entry("misc", &format!("PDG{}", edition.edition.as_deref().unwrap_or("")), &[
+            ("author", Some("{Particle Data Group}".to_string())),
+            ("title", title.map(|title| format!("{{{}}}", title))),
+            ("year", edition.edition.clone()),
+            ("note", edition.citation.as_deref().map(bibtex_escape)),
+        ]),
+        CitationFormat::Ris => ris_entry("GEN", &[
+            ("AU", Some("Particle Data Group".to_string())),
+            ("TI", title),
+            ("PY", edition.edition.clone()),
+            ("N1", edition.citation.clone()),
+        ]),
+    }
+}
+
+fn reference_entry(reference: &Reference, format: CitationFormat) -> String{
+    let inspire = reference.inspire_id.as_ref().map(|inspire_id| format!("{}{}", INSPIRE_URL, inspire_id));
+    match format {
+        CitationFormat::BibTex => bibtex_entry("article", &bibtex_key(reference), &[
+            ("author", reference.first_author().map(|author| format!("{} and others", author))),
+            ("title", reference.title.as_deref().map(|title| format!("{{{}}}", bibtex_escape(title)))),
+            ("journal", reference.publication_name.as_deref().map(bibtex_escape)),
+            ("year", reference.year.map(|year| year.to_string())),
+            ("doi", reference.doi.clone()),
+            ("url", inspire),
+            ("note", reference.document_id.as_ref().map(|document_id| format!("PDG document {}", document_id))),
+        ]),
+        CitationFormat::Ris => ris_entry("JOUR", &[
+            ("AU", reference.first_author()),
+            ("TI", reference.title.clone()),
+            ("JO", reference.publication_name.clone()),
+            ("PY", reference.year.map(|year| year.to_string())),
+            ("DO", reference.doi.clone()),
+            ("UR", inspire),
+            ("N1", reference.document_id.as_ref().map(|document_id| format!("PDG document {}", document_id))),
+        ]),
+    }
+}
+
+// The PDG edition first, then the references, separated by empty lines
+pub fn citations(edition: &Edition, references: &[Reference], format: CitationFormat) -> String{
+    std::iter::once(edition_entry(edition, format))
+        .chain(references.iter().map(|reference| reference_entry(reference, format)))
+        .collect::<Vec<String>>()
+        .join("\n\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edition() -> Edition{
+        Edition{
+            edition: Some("2024".to_string()),
+            citation: Some("S. Navas et al. (Particle Data Group), Phys. Rev. D 110, 030001 (2024)".to_string()),
+        }
+    }
+
+    fn reference() -> Reference{
+        Reference{
+            id: 1,
+            document_id: Some("ABLIKIM 2014D".to_string()),
+            publication_name: Some("PR D90 012001".to_string()),
+            year: Some(2014),
+            doi: Some("10.1103/PhysRevD.90.012001".to_string()),
+            inspire_id: Some("1286574".to_string()),
+            title: None,
+        }
+    }
+
+    #[test]
+    fn test_bibtex(){
+        let bibtex = citations(&edition(), &[reference()], CitationFormat::BibTex);
+        let expected = [
+            "@misc{PDG2024,",
+            "  author = {{Particle Data Group}},",
+            "  title = {{Review of Particle Physics}},",
+            "  year = {2024},",
+            "  note = {S. Navas et al. (Particle Data Group), Phys. Rev. D 110, 030001 (2024)},",
+            "}",
+            "",
+            "@article{ABLIKIM2014D,",
+            "  author = {Ablikim and others},",
+            "  journal = {PR D90 012001},",
+            "  year = {2014},",
+            "  doi = {10.1103/PhysRevD.90.012001},",
+            "  url = {https://inspirehep.net/literature/1286574},",
+            "  note = {PDG document ABLIKIM 2014D},",
+            "}",
+        ].join("\n");
+        assert_eq!(bibtex, expected);
+    }
+
+    #[test]
+    fn test_ris(){
+        let reference = Reference{ document_id: None, doi: None, ..reference() };
+        let ris = citations(&Edition::default(), &[reference], CitationFormat::Ris);
+        let expected = [
+            "TY  - GEN",
+            "AU  - Particle Data Group",
+            "TI  - Review of Particle Physics",
+            "ER  - ",
+            "",
+            "TY  - JOUR",
+            "JO  - PR D90 012001",
+            "PY  - 2014",
+            "UR  - https://inspirehep.net/literature/1286574",
+            "ER  - ",
+        ].join("\n");
+        assert_eq!(ris, expected);
+    }
+
+    #[test]
+    fn test_bibtex_key_and_escape(){
+        let reference = Reference{ document_id: None, ..reference() };
+        assert_eq!(bibtex_key(&reference), "PDGReference1");
+        assert_eq!(bibtex_escape("B & B 50%"), "B \\& B 50\\%");
+        assert_eq!(bibtex_escape("K_S^0 {x} $\\"), "K\\_S\\textasciicircum{}0 \\{x\\} \\$\\textbackslash{}");
+    }
+}
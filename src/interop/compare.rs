@@ -0,0 +1,137 @@
+use crate::interop::ExportMode;
+use crate::pdgdb::measured::Measured;
+use crate::pdgdb::Particle;
+
+// A PDG decay mode with its products as MC IDs, sorted so channels from files can be matched regardless of order
+#[derive(Debug, Clone)]
+pub struct PdgMode{
+    pub node_id: String,
+    pub products: Vec<i64>,
+    pub daughters: Vec<String>,
+    pub fraction: Option<Measured>,
+    pub is_measurement: bool, // false for limits and inclusive modes
+}
+
+// A channel of a decay file after its products have been translated to MC IDs
+#[derive(Debug, Clone)]
+pub struct FileChannel{
+    pub products: Vec<i64>,
+    pub branching_ratio: f64,
+    pub label: String, // the channel as written in the file, for reporting
+}
+
+#[derive(Debug)]
+pub struct ChannelComparison{
+    pub label: String,
+    pub file_value: Option<f64>, // None for PDG modes missing from the file
+    pub pdg: Option<PdgMode>, // None for channels absent from the PDG
+    pub pull: Option<f64>,
+}
+
+// Modes whose products all have an MC ID, the others cannot be matched to a file
+pub fn pdg_modes<F: Fn(&str) -> Option<i64>>(modes: &[ExportMode], resolve_mcid: F) -> Vec<PdgMode>{
+    modes
+        .iter()
+        .filter_map(|mode| {
+            let mut products = mode.daughters.iter().map(|name| resolve_mcid(name)).collect::<Option<Vec<i64>>>()?;
+            products.sort();
+            Some(PdgMode{
+                node_id: mode.decay.node_id.clone().unwrap_or_default(),
+                products,
+                daughters: mode.daughters.clone(),
+                fraction: Measured::from_decay(mode.decay),
+                is_measurement: mode.decay.is_exclusive_measurement(),
+            })
+        })
+        .collect()
+}
+
+// Total width in GeV with its errors, from the width or, linearised, from the lifetime
+pub fn pdg_width(particle: &Particle) -> Option<Measured>{
+    particle.measured_width().or(particle.decay_width.map(Measured::exact))
+}
+
+// Match every file channel to the PDG mode with the same products, then list the measured PDG modes the file lacks
+pub fn compare_channels(channels: &[FileChannel], modes: &[PdgMode]) -> Vec<ChannelComparison>{
+    let mut matched = vec![false; modes.len()];
+    let mut comparisons = Vec::new();
+    for channel in channels.iter() {
+        let mut products = channel.products.clone();
+        products.sort();
+        let found = modes.iter().position(|mode| mode.products == products);
+        let pdg = found.map(|i| {
+            matched[i] = true;
+            modes[i].clone()
+        });
+        let pull = pdg.as_ref()
+            .filter(|mode| mode.is_measurement)
+            .and_then(|mode| mode.fraction?.pull(channel.branching_ratio));
+        comparisons.push(ChannelComparison{
+            label: channel.label.clone(),
+            file_value: Some(channel.branching_ratio),
+            pdg,
+            pull,
+        });
+    }
+    for (mode, _) in modes.iter().zip(matched.iter()).filter(|(mode, matched)| !**matched && mode.is_measurement) {
+        comparisons.push(ChannelComparison{
+            label: mode.daughters.join(" "),
+            file_value: None,
+            pdg: Some(mode.clone()),
+            pull: None,
+        });
+    }
+    comparisons
+}
+
+// Channels absent from the PDG, measured PDG modes missing from the file and pulls beyond the limit fail a validation
+pub fn is_failure(comparison: &ChannelComparison, max_pull: f64) -> bool{
+    match (&comparison.pdg, comparison.file_value) {
+        (None, _) | (_, None) => true,
+        _ => comparison.pull.is_some_and(|pull| pull.abs() > max_pull),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mode(products: Vec<i64>, value: f64, error: f64) -> PdgMode{
+        PdgMode{
+            node_id: "S032.1".to_string(),
+            products,
+            daughters: vec!["K-".to_string(), "pi+".to_string()],
+            fraction: Some(Measured::new(value, error, error / 2.0)),
+            is_measurement: true,
+        }
+    }
+
+    fn channel(products: Vec<i64>, branching_ratio: f64) -> FileChannel{
+        FileChannel{ products, branching_ratio, label: String::new() }
+    }
+
+    #[test]
+    fn test_pdg_width(){
+        let mut muon = Particle::test_muon();
+        assert_eq!(pdg_width(&muon), None);
+        muon.decay_width = Some(3.0e-19);
+        assert_eq!(pdg_width(&muon), Some(Measured::exact(3.0e-19)));
+    }
+
+    #[test]
+    fn test_compare_channels(){
+        let modes = vec![mode(vec![-321, 211], 0.0395, 0.0003), mode(vec![-321, 111, 211], 0.144, 0.005)];
+        let channels = vec![channel(vec![211, -321], 0.0401), channel(vec![-211, 211], 0.0015)];
+        let comparisons = compare_channels(&channels, &modes);
+        assert_eq!(comparisons.len(), 3);
+        assert!((comparisons[0].pull.unwrap() - 2.0).abs() < 1e-9);
+        assert!(comparisons[1].pdg.is_none());
+        assert!(comparisons[2].file_value.is_none());
+        assert_eq!(comparisons[2].pdg.as_ref().unwrap().products, vec![-321, 111, 211]);
+
+        assert!(!is_failure(&comparisons[0], 3.0));
+        assert!(is_failure(&comparisons[0], 1.5));
+        assert!(is_failure(&comparisons[1], 3.0));
+        assert!(is_failure(&comparisons[2], 3.0));
+    }
+}
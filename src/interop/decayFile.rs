@@ -0,0 +1,230 @@
+use std::collections::{HashMap, HashSet};
+
+// The decays of a generator decay file, an EvtGen DECAY.DEC or Pythia `oneChannel`/`addChannel` settings
+#[derive(Debug, Default, PartialEq)]
+pub struct DecayFile{
+    pub decays: Vec<FileDecay>,
+    pub conjugate_decays: Vec<ConjugateDecay>, // EvtGen `CDecay` parents, validated with the conjugated channels of their antiparticle
+}
+
+// `CDecay anti-D0` decays as the charge conjugate of the `Decay D0` block
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConjugateDecay{
+    pub parent: String, // EvtGen name, aliases resolved
+    pub line: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileDecay{
+    pub parent: String, // EvtGen name or Pythia MC ID or name, aliases resolved
+    pub channels: Vec<FileDecayChannel>,
+    pub line: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileDecayChannel{
+    pub branching_ratio: f64,
+    pub daughters: Vec<String>, // EvtGen names, aliases resolved, or Pythia MC IDs
+    pub model: Vec<String>, // EvtGen model with PHOTOS and its parameters, empty for Pythia
+    pub line: usize,
+}
+
+// Written before the model of a channel to add final-state radiation, i.e., `K- pi+ PHOTOS PHSP;`
+const EVTGEN_PHOTOS: &str = "PHOTOS";
+
+// Every daughter as an MC ID, the error names the first daughter without one
+pub fn channel_products<F: Fn(&str) -> Option<i64>>(channel: &FileDecayChannel, resolve_mcid: F) -> Result<Vec<i64>, String>{
+    if channel.daughters.is_empty() {
+        return Err("no daughters".to_string());
+    }
+    channel.daughters
+        .iter()
+        .map(|daughter| resolve_mcid(daughter).ok_or(format!("unknown particle {}", daughter)))
+        .collect()
+}
+
+fn parse_number(token: &str, line: usize) -> Result<f64, String>{
+    token.parse::<f64>().map_err(|_| format!("line {}: invalid branching ratio {}", line, token))
+}
+
+// `BR daughters... [PHOTOS] MODEL parameters...;` where the parameters are numbers or names of `Define`,
+// so the model is the last token before them
+fn parse_evtgen_channel(content: &str, aliases: &HashMap<String, String>, defines: &HashSet<String>, line: usize) -> Result<FileDecayChannel, String>{
+    let content = content
+        .strip_suffix(';')
+        .or_else(|| content.split_once(';').map(|(channel, _)| channel))
+        .ok_or(format!("line {}: a channel ends with ;", line))?;
+    let mut tokens = content.split_whitespace();
+    let branching_ratio = parse_number(tokens.next().unwrap_or(""), line)?;
+    let tokens = tokens.collect::<Vec<&str>>();
+    let is_parameter = |token: &&&str| token.parse::<f64>().is_ok() || defines.contains(**token);
+    let parameters = tokens.iter().rev().take_while(is_parameter).count();
+    let mut model_start = match tokens.len().checked_sub(parameters + 1) {
+        Some(model_start) if model_start > 0 => model_start,
+        _ => return Err(format!("line {}: a channel needs daughters and a model", line)),
+    };
+    if model_start > 1 && tokens[model_start - 1].eq_ignore_ascii_case(EVTGEN_PHOTOS) {
+        model_start -= 1;
+    }
+    let daughters = tokens[..model_start]
+        .iter()
+        .map(|token| aliases.get(*token).cloned().unwrap_or(token.to_string()))
+        .collect::<Vec<String>>();
+    let model = tokens[model_start..].iter().map(|token| token.to_string()).collect();
+    Ok(FileDecayChannel{ branching_ratio, daughters, model, line })
+}
+
+// `421:addChannel = 1 0.0389 0 -321 211`, that is onMode bRatio meMode products
+fn parse_pythia_channel(value: &str, line: usize) -> Result<FileDecayChannel, String>{
+    let fields = value.split_whitespace().collect::<Vec<&str>>();
+    if fields.len() < 4 {
+        return Err(format!("line {}: a channel needs onMode, bRatio, meMode and products", line));
+    }
+    Ok(FileDecayChannel{
+        branching_ratio: parse_number(fields[1], line)?,
+        daughters: fields[3..].iter().map(|token| token.to_string()).collect(),
+        model: Vec::new(),
+        line,
+    })
+}
+
+pub fn parse_decay_file(text: &str) -> Result<DecayFile, String>{
+    let mut file = DecayFile::default();
+    let mut aliases = HashMap::new();
+    let mut defines = HashSet::new();
+    let mut current: Option<FileDecay> = None;
+    for (index, line) in text.lines().enumerate() {
+        let number = index + 1;
+        let content = line.split('#').next().unwrap_or("").trim();
+        if content.is_empty() {
+            continue;
+        }
+        let fields = content.split_whitespace().collect::<Vec<&str>>();
+        match fields.as_slice() {
+            ["Alias", alias, name, ..] => {
+                aliases.insert(alias.to_string(), name.to_string());
+            },
+            ["Define", name, ..] => {
+                defines.insert(name.to_string());
+            },
+            ["Decay", parent, ..] => {
+                if current.is_some() {
+                    return Err(format!("line {}: Decay {} before Enddecay", number, parent));
+                }
+                let parent = aliases.get(*parent).cloned().unwrap_or(parent.to_string());
+                current = Some(FileDecay{ parent, channels: Vec::new(), line: number });
+            },
+            ["Enddecay", ..] => match current.take() {
+                Some(decay) => file.decays.push(decay),
+                None => return Err(format!("line {}: Enddecay without Decay", number)),
+            },
+            ["CDecay", parent, ..] => file.conjugate_decays.push(ConjugateDecay{
+                parent: aliases.get(*parent).cloned().unwrap_or(parent.to_string()),
+                line: number,
+            }),
+            ["End", ..] => break,
+            _ if current.is_some() => {
+                let channel = parse_evtgen_channel(content, &aliases, &defines, number)?;
+                current.as_mut().unwrap().channels.push(channel);
+            },
+            _ => {
+                // Pythia keys are case insensitive
+                let (key, value) = match content.split_once('=') {
+                    Some((key, value)) => (key.trim(), value.trim()),
+                    None => continue,
+                };
+                let (parent, setting) = match key.split_once(':') {
+                    Some((parent, setting)) => (parent.trim(), setting.trim().to_lowercase()),
+                    None => continue,
+                };
+                if setting != "onechannel" && setting != "addchannel" {
+                    continue;
+                }
+                let channel = parse_pythia_channel(value, number)?;
+                let position = file.decays.iter().position(|decay| decay.parent == parent);
+                match position {
+                    Some(i) if setting == "addchannel" => file.decays[i].channels.push(channel),
+                    Some(i) => file.decays[i].channels = vec![channel],
+                    None => file.decays.push(FileDecay{ parent: parent.to_string(), channels: vec![channel], line: number }),
+                }
+            },
+        }
+    }
+    if let Some(decay) = current {
+        return Err(format!("line {}: Decay {} without Enddecay", decay.line, decay.parent));
+    }
+    Ok(file)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_evtgen(){
+        let text = "\
+# D0 decays
+Define dm 0.507e12
+Alias MyKS K_S0
+Decay D0
+0.0389   K-  pi+                PHSP;
+0.0119   MyKS pi0   PHSP; # comment
+0.0142   K- pi+ pi0  D_DALITZ;
+0.0010   K- X0 pi+ PHOTOS PHSP;
+0.0020   K- pi+ VSS_BMIX dm;
+0.0030   K- pi+ MY_MODEL 0.5 dm;
+0.0040   K- pi+ PHOTOS;
+Enddecay
+CDecay anti-D0
+End
+";
+        let file = parse_decay_file(text).unwrap();
+        assert_eq!(file.decays.len(), 1);
+        assert_eq!(file.decays[0].parent, "D0");
+        assert_eq!(file.decays[0].channels[1].daughters, vec!["K_S0", "pi0"]);
+        assert_eq!(file.decays[0].channels[1].model, vec!["PHSP"]);
+        assert_eq!(file.decays[0].channels[3].model, vec!["PHOTOS", "PHSP"]);
+        assert_eq!(file.decays[0].channels[4].model, vec!["VSS_BMIX", "dm"]);
+        // a model outside the EvtGen distribution is still found before its parameters
+        assert_eq!(file.decays[0].channels[5].daughters, vec!["K-", "pi+"]);
+        assert_eq!(file.decays[0].channels[5].model, vec!["MY_MODEL", "0.5", "dm"]);
+        // PHOTOS alone is the model
+        assert_eq!(file.decays[0].channels[6].model, vec!["PHOTOS"]);
+        assert_eq!(file.conjugate_decays, vec![ConjugateDecay{ parent: "anti-D0".to_string(), line: 13 }]);
+
+        let resolve = |name: &str| match name {
+            "K-" => Some(-321),
+            "pi+" => Some(211),
+            "pi0" => Some(111),
+            _ => None,
+        };
+        assert_eq!(channel_products(&file.decays[0].channels[2], resolve), Ok(vec![-321, 211, 111]));
+        assert_eq!(channel_products(&file.decays[0].channels[1], resolve), Err("unknown particle K_S0".to_string()));
+        // an unknown daughter in the middle does not cut the channel short
+        assert_eq!(channel_products(&file.decays[0].channels[3], resolve), Err("unknown particle X0".to_string()));
+        assert_eq!(channel_products(&file.decays[0].channels[4], resolve), Ok(vec![-321, 211]));
+
+        assert!(parse_decay_file("Decay D0\n0.5 K- pi+ PHSP;\n").is_err());
+        assert!(parse_decay_file("Decay D0\nx K- pi+ PHSP;\nEnddecay\n").unwrap_err().starts_with("line 2"));
+        assert!(parse_decay_file("Decay D0\n0.5 PHSP;\nEnddecay\n").unwrap_err().contains("daughters and a model"));
+        assert!(parse_decay_file("Decay D0\n0.5 K- pi+ PHSP\nEnddecay\n").unwrap_err().contains("ends with ;"));
+    }
+
+    #[test]
+    fn test_parse_pythia(){
+        let text = "\
+421:onMode = off
+421:addChannel = 1 0.5 0 -321 211
+421:oneChannel = 1 0.0389 0 -321 211
+421:addChannel = 1 0.144 0 -321 211 111
+15:ONECHANNEL = 1 1.0 0 16 -211
+";
+        let file = parse_decay_file(text).unwrap();
+        assert_eq!(file.decays.len(), 2);
+        assert_eq!(file.decays[0].parent, "421");
+        assert_eq!(file.decays[0].channels.len(), 2);
+        assert_eq!(file.decays[0].channels[0].branching_ratio, 0.0389);
+        assert_eq!(file.decays[0].channels[1].daughters, vec!["-321", "211", "111"]);
+        assert_eq!(file.decays[1].channels[0].daughters, vec!["16", "-211"]);
+    }
+}
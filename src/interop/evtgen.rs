@@ -0,0 +1,85 @@
+use crate::interop::ExportMode;
+use crate::pdgdb::measured::{Kind, Measured};
+use crate::pdgdb::naming::{name_table, Convention};
+
+pub fn evtgen_file(blocks: &[String]) -> String{
+    let mut lines = vec!["# Decay table generated by pdgQuery from the PDG database".to_string()];
+    lines.extend(blocks.iter().cloned());
+    lines.push("End".to_string());
+    lines.join("\n\n")
+}
+
+// Names that are not in the name table are used as they are
+pub fn evtgen_name(pdg_name: &str) -> String{
+    name_table().translate(pdg_name, Convention::Pdg, Convention::EvtGen).unwrap_or(pdg_name).to_string()
+}
+
+pub fn pdg_name(evtgen_name: &str) -> String{
+    name_table().translate(evtgen_name, Convention::EvtGen, Convention::Pdg).unwrap_or(evtgen_name).to_string()
+}
+
+// One `Decay ... Enddecay` block, the measured exclusive modes are normalised to a total of one with the PHSP model.
+// Limits cannot be used as branching fractions, they are kept as comments so the user can decide.
+pub fn decay_block(parent: &str, modes: &[ExportMode]) -> String{
+    let used = modes.iter().filter(|mode| mode.decay.is_exclusive_measurement()).collect::<Vec<_>>();
+    let total: f64 = used.iter().filter_map(|mode| mode.decay.value).sum();
+
+    let mut lines = vec![format!(
+        "# {} modes with measured branching fractions, normalised from a sum of {:.6}",
+        used.len(),
+        total,
+    )];
+    lines.push(format!("Decay {}", evtgen_name(parent)));
+    for mode in modes.iter() {
+        let daughters = mode.daughters.iter().map(|name| evtgen_name(name)).collect::<Vec<String>>().join(" ");
+        let value = mode.decay.value.unwrap_or(f64::NAN);
+        match Measured::from_decay(mode.decay).map(|measured| measured.kind) {
+            Some(Kind::UpperLimit{ .. }) => lines.push(format!("# < {:.4e} {} PHSP;", value, daughters)),
+            Some(Kind::LowerLimit{ .. }) => lines.push(format!("# > {:.4e} {} PHSP;", value, daughters)),
+            _ if mode.decay.is_exclusive_measurement() => {
+                lines.push(format!("{:.8} {} PHSP;", value / total, daughters));
+            },
+            // inclusive modes and modes without a value would double count
+            _ => lines.push(format!("# not used ({}) {}", mode.decay.display_value.as_deref().unwrap_or("inclusive"), daughters)),
+        }
+    }
+    lines.push("Enddecay".to_string());
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdgdb::ParticleDecay;
+
+    fn decay(value: f64, limit_type: Option<&str>) -> ParticleDecay{
+        ParticleDecay::test("S032.1", value, limit_type)
+    }
+
+    #[test]
+    fn test_evtgen_name(){
+        assert_eq!(evtgen_name("K(S)0"), "K_S0");
+        assert_eq!(evtgen_name("nubar_e"), "anti-nu_e");
+        assert_eq!(evtgen_name("pi+"), "pi+");
+        assert_eq!(pdg_name("anti-K*0"), "K*(892)bar0");
+        assert_eq!(pdg_name("pi+"), "pi+");
+    }
+
+    #[test]
+    fn test_decay_block(){
+        let decays = [decay(0.03, None), decay(0.01, None), decay(1.2e-5, Some("U"))];
+        let daughters = |names: &[&str]| names.iter().map(|name| name.to_string()).collect::<Vec<String>>();
+        let modes = vec![
+            ExportMode{ decay: &decays[0], daughters: daughters(&["K-", "pi+"]) },
+            ExportMode{ decay: &decays[1], daughters: daughters(&["K(S)0", "pi0"]) },
+            ExportMode{ decay: &decays[2], daughters: daughters(&["e+", "e-"]) },
+        ];
+        let block = decay_block("D0", &modes);
+        let lines = block.lines().collect::<Vec<&str>>();
+        assert_eq!(lines[1], "Decay D0");
+        assert_eq!(lines[2], "0.75000000 K- pi+ PHSP;");
+        assert_eq!(lines[3], "0.25000000 K_S0 pi0 PHSP;");
+        assert_eq!(lines[4], "# < 1.2000e-5 e+ e- PHSP;");
+        assert_eq!(lines[5], "Enddecay");
+    }
+}
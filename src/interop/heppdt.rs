@@ -0,0 +1,109 @@
+use crate::pdgdb::Particle;
+use crate::pdgdb::dataType::DataType;
+
+// Fixed column PDG mass_width table (*.mcd) as read by HepPDT::addPDGParticles, one M and one W line per particle
+pub fn heppdt_file(entries: &[String]) -> String{
+    let mut lines = vec![
+        "* MASS AND WIDTH TABLE generated by pdgQuery from the PDG database".to_string(),
+        "* columns: 1 M or W, 2-9 MC ID, 35-49 value (GeV), 51-58 positive error, 60-67 negative error, 69- name and charge".to_string(),
+    ];
+    lines.extend(entries.iter().filter(|entry| !entry.is_empty()).cloned());
+    lines.join("\n")
+}
+
+// Fortran style exponent with a sign and two digits, i.e., 1.3957039E-01
+fn fortran_exp(value: f64, precision: usize) -> String{
+    let formatted = format!("{:.*E}", precision, value);
+    let (mantissa, exponent) = formatted.split_once('E').unwrap();
+    let exponent = exponent.parse::<i32>().unwrap();
+    format!("{}E{}{:02}", mantissa, if exponent < 0 { '-' } else { '+' }, exponent.abs())
+}
+
+fn charge_symbol(charge: f64) -> String{
+    match charge.round() as i64 {
+        0 => "0".to_string(),
+        q if q > 0 => "+".repeat(q as usize),
+        q => "-".repeat(q.unsigned_abs() as usize),
+    }
+}
+
+// The mcd name does not carry the charge, "pi+" is written as "pi" with charge "+"
+fn split_name(name: &str, charge: &str) -> String{
+    name.strip_suffix(charge)
+        .filter(|base| !base.is_empty())
+        .unwrap_or(name)
+        .replace(' ', "_")
+}
+
+fn mcd_line(kind: char, mcid: i64, value: f64, plus_error: f64, minus_error: f64, name: &str, charge: &str) -> String{
+    format!(
+        "{}{:>8}{:25}{:<15} {:<8} {:<8} {} {}",
+        kind,
+        mcid,
+        "",
+        fortran_exp(value, 7),
+        format!("+{}", fortran_exp(plus_error, 1)),
+        format!("-{}", fortran_exp(minus_error, 1)),
+        name,
+        charge,
+    )
+}
+
+// Empty when the particle has no MC ID, the W line is only written when the width is known
+pub fn heppdt_entry(particle: &Particle) -> String{
+    let (mcid, name) = match (particle.pdgid, particle.name.as_deref()) {
+        (Some(mcid), Some(name)) => (mcid, name),
+        _ => return String::new(),
+    };
+    let charge = charge_symbol(particle.charge.unwrap_or_default());
+    let name = split_name(name, &charge);
+    let errors = |kind: DataType| particle
+        .summary_measurement(&kind)
+        .map_or((0.0, 0.0), |measurement| (measurement.plus_error.unwrap_or_default(), measurement.minus_error.unwrap_or_default()));
+
+    let mut lines = Vec::new();
+    if let Some(mass) = particle.mass {
+        let (plus_error, minus_error) = errors(DataType::Mass);
+        lines.push(mcd_line('M', mcid, mass, plus_error, minus_error, &name, &charge));
+    }
+    if let Some(width) = particle.decay_width {
+        let (plus_error, minus_error) = errors(DataType::Width);
+        lines.push(mcd_line('W', mcid, width, plus_error, minus_error, &name, &charge));
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fortran_exp(){
+        assert_eq!(fortran_exp(0.13957039, 7), "1.3957039E-01");
+        assert_eq!(fortran_exp(91.1876, 4), "9.1188E+01");
+        assert_eq!(fortran_exp(0.0, 1), "0.0E+00");
+    }
+
+    #[test]
+    fn test_split_name(){
+        assert_eq!(split_name("pi+", "+"), "pi");
+        assert_eq!(split_name("D0", "0"), "D");
+        assert_eq!(split_name("gamma", "0"), "gamma");
+        assert_eq!(split_name("Delta(1232)++", "++"), "Delta(1232)");
+        assert_eq!(split_name("p", "+"), "p");
+    }
+
+    #[test]
+    fn test_heppdt_entry(){
+        let mut muon = Particle::test_muon();
+        muon.mass = Some(0.1056583755);
+        let line = heppdt_entry(&muon);
+        assert_eq!(line, "M      13                         1.0565838E-01   +0.0E+00 -0.0E+00 mu -");
+        assert_eq!(&line[0..1], "M");
+        assert_eq!(line[1..9].trim(), "13");
+        assert_eq!(line[34..49].trim(), "1.0565838E-01");
+
+        muon.pdgid = None;
+        assert_eq!(heppdt_entry(&muon), "");
+    }
+}
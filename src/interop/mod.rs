@@ -0,0 +1,56 @@
+pub mod evtgen;
+pub mod pythia;
+pub mod root;
+pub mod heppdt;
+pub mod slha;
+pub mod compare;
+pub mod decayFile;
+pub mod citation;
+
+use crate::pdgdb::ParticleDecay;
+use crate::pdgdb::measured::{Kind, Measured};
+use crate::pdgdb::queries::decayQueries::DecayQuery;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExportFormat{
+    EvtGen, // DECAY.DEC
+    Pythia, // Pythia8 ParticleData XML
+    Root,   // ROOT TDatabasePDG pdg_table.txt
+    HepPdt, // PDG mass_width table read by HepPDT
+    Slha,   // SLHA DECAY blocks
+}
+
+// A decay mode of a particle together with its daughters, which are only stored in pdgdecay
+pub struct ExportMode<'a>{
+    pub decay: &'a ParticleDecay,
+    pub daughters: Vec<String>, // PDG names, repeated by multiplicity and sorted
+}
+
+// The comment text of a mode that is not written as a channel. Limits cannot be used as branching fractions,
+// they keep their side and value so the user can decide, inclusive modes are only marked as not used
+pub fn unused_mode_note(decay: &ParticleDecay) -> String{
+    let limit = match Measured::from_decay(decay).map(|measured| measured.kind) {
+        Some(Kind::UpperLimit{ .. }) => "<",
+        Some(Kind::LowerLimit{ .. }) => ">",
+        _ => "not used",
+    };
+    format!("{} {:.4e}", limit, decay.value.unwrap_or(f64::NAN))
+}
+
+pub fn export_modes<'a>(decay_query: &DecayQuery, decays: &'a [ParticleDecay]) -> Vec<ExportMode<'a>>{
+    decays
+        .iter()
+        .filter_map(|decay| {
+            let channel = decay_query.map_decay(decay.node_id.as_deref()?).ok()?;
+            let mut daughters = channel.daughters
+                .iter()
+                .flat_map(|(name, multiplicity)| std::iter::repeat_n(name.clone(), *multiplicity as usize))
+                .collect::<Vec<String>>();
+            if daughters.is_empty() {
+                return None;
+            }
+            daughters.sort();
+            Some(ExportMode{ decay, daughters })
+        })
+        .collect()
+}
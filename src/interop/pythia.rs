@@ -0,0 +1,123 @@
+use crate::interop::{unused_mode_note, ExportMode};
+use crate::pdgdb::mcid::McKind;
+use crate::pdgdb::Particle;
+
+pub fn xml_escape(text: &str) -> String{
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+pub fn pythia_file(entries: &[String]) -> String{
+    let mut lines = vec![
+        "<chapter name=\"Particle Data\">".to_string(),
+        "<!-- generated by pdgQuery from the PDG database, read with ParticleData::readXML -->".to_string(),
+    ];
+    lines.extend(entries.iter().cloned());
+    lines.push("</chapter>".to_string());
+    lines.join("\n\n")
+}
+
+// One `<particle>` entry with a `<channel>` per measured exclusive mode.
+// Pythia renormalises the open channels itself, so the branching fractions are written as measured.
+// Channels whose products have no MC ID and the modes of unused_mode_note are kept as comments.
+pub fn particle_entry<F>(particle: &Particle, anti_name: Option<&str>, modes: &[ExportMode], resolve_mcid: F) -> Option<String>
+where
+    F: Fn(&str) -> Option<i64>,
+{
+    let mcid = particle.mcid()?;
+    let mut attributes = vec![
+        format!("id=\"{}\"", mcid.0),
+        format!("name=\"{}\"", xml_escape(particle.name.as_deref()?)),
+    ];
+    if let Some(anti_name) = anti_name {
+        attributes.push(format!("antiName=\"{}\"", xml_escape(anti_name)));
+    }
+    if let Some(spin_type) = mcid.spin_multiplicity() {
+        attributes.push(format!("spinType=\"{}\"", spin_type));
+    }
+    if let Some(charge) = particle.charge {
+        attributes.push(format!("chargeType=\"{}\"", (charge * 3.0).round() as i64));
+    }
+    let color_type = match mcid.kind() {
+        McKind::Quark => if mcid.0 > 0 { 1 } else { -1 },
+        McKind::GaugeBoson if mcid.0 == 21 => 2,
+        _ => 0,
+    };
+    attributes.push(format!("colType=\"{}\"", color_type));
+    if let Some(mass) = particle.mass {
+        attributes.push(format!("m0=\"{:.8e}\"", mass));
+    }
+    if let Some(width) = particle.decay_width {
+        attributes.push(format!("mWidth=\"{:.8e}\"", width));
+    }
+    if let Some(ctau) = particle.ctau() {
+        attributes.push(format!("tau0=\"{:.8e}\"", ctau));
+    }
+
+    let mut lines = vec![format!("<particle {}>", attributes.join(" "))];
+    for mode in modes.iter() {
+        let names = mode.daughters.join(" ");
+        if !mode.decay.is_exclusive_measurement() {
+            lines.push(format!(" <!-- {}: {} -->", unused_mode_note(mode.decay), xml_escape(&names)));
+            continue;
+        }
+        let products = mode.daughters.iter().map(|name| resolve_mcid(name)).collect::<Option<Vec<i64>>>();
+        match products {
+            Some(products) => lines.push(format!(
+                " <channel onMode=\"1\" bRatio=\"{:.8e}\" meMode=\"0\" products=\"{}\"/>",
+                mode.decay.value.unwrap_or_default(),
+                products.iter().map(|id| id.to_string()).collect::<Vec<String>>().join(" "),
+            )),
+            None => lines.push(format!(" <!-- no MC ID for all products: {} -->", xml_escape(&names))),
+        }
+    }
+    lines.push("</particle>".to_string());
+    Some(lines.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdgdb::ParticleDecay;
+
+    fn decay(value: f64, limit_type: Option<&str>) -> ParticleDecay{
+        ParticleDecay::test("S004.1", value, limit_type)
+    }
+
+    #[test]
+    fn test_particle_entry(){
+        let mut muon = Particle::test_muon();
+        muon.mass = Some(0.1056583755);
+        muon.decay_width = Some(2.99598e-19);
+        let decays = [decay(1.0, None), decay(4.2e-13, Some("U")), decay(0.014, None)];
+        let names = |names: &[&str]| names.iter().map(|name| name.to_string()).collect::<Vec<String>>();
+        let modes = vec![
+            ExportMode{ decay: &decays[0], daughters: names(&["e-", "nu_mu", "nubar_e"]) },
+            ExportMode{ decay: &decays[1], daughters: names(&["e-", "gamma"]) },
+            ExportMode{ decay: &decays[2], daughters: names(&["e-", "gamma", "unknown"]) },
+        ];
+        let resolve = |name: &str| match name {
+            "e-" => Some(11),
+            "nu_mu" => Some(14),
+            "nubar_e" => Some(-12),
+            "gamma" => Some(22),
+            _ => None,
+        };
+        let entry = particle_entry(&muon, Some("mu+"), &modes, resolve).unwrap();
+        let lines = entry.lines().collect::<Vec<&str>>();
+        assert!(lines[0].starts_with("<particle id=\"13\" name=\"mu-\" antiName=\"mu+\" spinType=\"2\" chargeType=\"-3\" colType=\"0\" m0=\"1.05658375e-1\""));
+        assert!(lines[0].contains("tau0=\"6.586"));
+        assert_eq!(lines[1], " <channel onMode=\"1\" bRatio=\"1.00000000e0\" meMode=\"0\" products=\"11 14 -12\"/>");
+        assert_eq!(lines[2], " <!-- < 4.2000e-13: e- gamma -->");
+        assert_eq!(lines[3], " <!-- no MC ID for all products: e- gamma unknown -->");
+        assert_eq!(lines[4], "</particle>");
+    }
+
+    #[test]
+    fn test_xml_escape(){
+        assert_eq!(xml_escape("a<b>&\"c\""), "a&lt;b&gt;&amp;&quot;c&quot;");
+        assert_eq!(xml_escape("eta'(958)"), "eta'(958)");
+    }
+}
@@ -0,0 +1,123 @@
+use crate::interop::ExportMode;
+use crate::pdgdb::mcid::McKind;
+use crate::pdgdb::Particle;
+
+pub fn root_file(entries: &[String]) -> String{
+    let mut lines = vec![
+        "# ROOT TDatabasePDG table generated by pdgQuery from the PDG database, read with TDatabasePDG::ReadPDGTable".to_string(),
+        "# particle: index name code 0 class charge(|e|/3) mass width 2I I3 2J flavor tracking_code n_channels".to_string(),
+        "# antiparticle: index name code index_of_particle".to_string(),
+        "# channel: index type branching_ratio n_daughters daughter_codes".to_string(),
+    ];
+    lines.extend(entries.iter().cloned());
+    lines.join("\n")
+}
+
+fn class_name(kind: McKind) -> &'static str{
+    match kind {
+        McKind::Quark => "Quark",
+        McKind::Lepton => "Lepton",
+        McKind::GaugeBoson => "GaugeBoson",
+        McKind::Higgs => "Boson",
+        McKind::Meson => "Meson",
+        McKind::Baryon => "Baryon",
+        McKind::Diquark => "DiQuark",
+        McKind::Nucleus => "Ion",
+        McKind::Other => "Unknown",
+    }
+}
+
+// twice the isospin from pdgparticle.quantum_i, i.e., "1/2" gives 1
+fn twice_isospin(quantum_i: Option<&str>) -> i64{
+    let quantum_i = quantum_i.unwrap_or("").trim();
+    match quantum_i.split_once('/') {
+        Some((numerator, "2")) => numerator.parse().unwrap_or(0),
+        Some(_) => 0,
+        None => quantum_i.parse::<i64>().map_or(0, |i| 2 * i),
+    }
+}
+
+// The particle line with its decay channels and, for particles that are not self-conjugate,
+// the antiparticle line which refers back to the particle. Returns None without an MC ID.
+pub fn root_entry<F>(index: usize, particle: &Particle, anti_name: Option<&str>, modes: &[ExportMode], resolve_mcid: F) -> Option<String>
+where
+    F: Fn(&str) -> Option<i64>,
+{
+    let mcid = particle.mcid()?;
+    let name = particle.name.as_deref()?;
+    let channels = modes
+        .iter()
+        .filter(|mode| mode.decay.is_exclusive_measurement())
+        .filter_map(|mode| {
+            let products = mode.daughters.iter().map(|name| resolve_mcid(name)).collect::<Option<Vec<i64>>>()?;
+            Some((mode.decay.value.unwrap_or_default(), products))
+        })
+        .collect::<Vec<(f64, Vec<i64>)>>();
+
+    let mut lines = vec![format!(
+        "{:>5} {:<20} {:>10} {:>2} {:<10} {:>3} {:.6e} {:.6e} {:>3} {:>3} {:>3} {:>3} {:>3} {:>3}",
+        index,
+        name.replace(' ', "_"),
+        mcid.0,
+        0,
+        class_name(mcid.kind()),
+        (particle.charge.unwrap_or_default() * 3.0).round() as i64,
+        particle.mass.unwrap_or_default(),
+        particle.decay_width.unwrap_or_default(),
+        twice_isospin(particle.i_spin.as_deref()),
+        0,
+        mcid.spin_multiplicity().map_or(0, |multiplicity| multiplicity - 1),
+        0,
+        -1,
+        channels.len(),
+    )];
+    for (i, (branching_ratio, products)) in channels.iter().enumerate() {
+        lines.push(format!(
+            "{:>13} {:>3} {:.6e} {:>3} {}",
+            i,
+            0,
+            branching_ratio,
+            products.len(),
+            products.iter().map(|id| format!("{:>8}", id)).collect::<Vec<String>>().join(" "),
+        ));
+    }
+    if let Some(anti_name) = anti_name {
+        lines.push(format!("{:>5} {:<20} {:>10} {:>2}", index + 1, anti_name.replace(' ', "_"), -mcid.0, index));
+    }
+    Some(lines.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdgdb::ParticleDecay;
+
+    #[test]
+    fn test_root_entry(){
+        let mut muon = Particle::test_muon();
+        muon.mass = Some(0.1056583755);
+        muon.decay_width = Some(2.99598e-19);
+        let decay = ParticleDecay::test("S004.1", 1.0, None);
+        let modes = vec![ExportMode{ decay: &decay, daughters: vec!["e-".to_string(), "nu_mu".to_string(), "nubar_e".to_string()] }];
+        let resolve = |name: &str| match name {
+            "e-" => Some(11),
+            "nu_mu" => Some(14),
+            "nubar_e" => Some(-12),
+            _ => None,
+        };
+        let entry = root_entry(4, &muon, Some("mu+"), &modes, resolve).unwrap();
+        let lines = entry.lines().collect::<Vec<&str>>();
+        let fields = lines[0].split_whitespace().collect::<Vec<&str>>();
+        assert_eq!(fields, vec!["4", "mu-", "13", "0", "Lepton", "-3", "1.056584e-1", "2.995980e-19", "1", "0", "1", "0", "-1", "1"]);
+        assert_eq!(lines[1].split_whitespace().collect::<Vec<&str>>(), vec!["0", "0", "1.000000e0", "3", "11", "14", "-12"]);
+        assert_eq!(lines[2].split_whitespace().collect::<Vec<&str>>(), vec!["5", "mu+", "-13", "4"]);
+    }
+
+    #[test]
+    fn test_twice_isospin(){
+        assert_eq!(twice_isospin(Some("1/2")), 1);
+        assert_eq!(twice_isospin(Some("1")), 2);
+        assert_eq!(twice_isospin(Some("?")), 0);
+        assert_eq!(twice_isospin(None), 0);
+    }
+}
@@ -0,0 +1,144 @@
+use crate::interop::{unused_mode_note, ExportMode};
+
+// A DECAY block of an SLHA file, widths in GeV
+#[derive(Debug, Clone, PartialEq)]
+pub struct SlhaDecay{
+    pub mcid: i64,
+    pub width: f64,
+    pub channels: Vec<SlhaChannel>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SlhaChannel{
+    pub branching_ratio: f64,
+    pub products: Vec<i64>, // MC IDs, NDA of them
+}
+
+pub fn slha_file(blocks: &[String]) -> String{
+    let mut lines = vec![
+        "# SLHA decay tables generated by pdgQuery from the PDG database".to_string(),
+        "# branching ratios are written as measured, they are not normalised".to_string(),
+    ];
+    lines.extend(blocks.iter().filter(|block| !block.is_empty()).cloned());
+    lines.join("\n")
+}
+
+// One `DECAY <mcid> <width>` block followed by `BR NDA ID1 ID2 ...` for every measured exclusive mode.
+// Modes whose products have no MC ID and the modes of unused_mode_note are kept as comments.
+pub fn decay_block<F>(mcid: i64, parent: &str, width: Option<f64>, modes: &[ExportMode], resolve_mcid: F) -> String
+where
+    F: Fn(&str) -> Option<i64>,
+{
+    let mut lines = vec![
+        "#         PDG            Width".to_string(),
+        format!("DECAY {:>9}   {:.8e}   # {}", mcid, width.unwrap_or_default(), parent),
+        "#          BR         NDA      ID1       ID2       ...".to_string(),
+    ];
+    for mode in modes.iter() {
+        let names = mode.daughters.join(" ");
+        if !mode.decay.is_exclusive_measurement() {
+            lines.push(format!("#   {}   # {} -> {}", unused_mode_note(mode.decay), parent, names));
+            continue;
+        }
+        let products = mode.daughters.iter().map(|name| resolve_mcid(name)).collect::<Option<Vec<i64>>>();
+        match products {
+            Some(products) => lines.push(format!(
+                "     {:.8e}   {:>3}   {}   # {} -> {}",
+                mode.decay.value.unwrap_or_default(),
+                products.len(),
+                products.iter().map(|id| format!("{:>8}", id)).collect::<Vec<String>>().join("  "),
+                parent,
+                names,
+            )),
+            None => lines.push(format!("#   no MC ID for all products   # {} -> {}", parent, names)),
+        }
+    }
+    lines.join("\n")
+}
+
+// Reads the DECAY blocks, every other block is skipped. The keywords are case insensitive as in the SLHA standard.
+pub fn parse_slha(text: &str) -> Result<Vec<SlhaDecay>, String>{
+    let mut decays: Vec<SlhaDecay> = Vec::new();
+    let mut in_decay = false;
+    for (number, line) in text.lines().enumerate() {
+        let content = line.split('#').next().unwrap_or("").trim();
+        if content.is_empty() {
+            continue;
+        }
+        let fields = content.split_whitespace().collect::<Vec<&str>>();
+        let error = |message: &str| format!("line {}: {}: {}", number + 1, message, line.trim());
+        if fields[0].eq_ignore_ascii_case("DECAY") {
+            let (mcid, width) = match fields.as_slice() {
+                [_, mcid, width, ..] => (
+                    mcid.parse::<i64>().map_err(|_| error("invalid PDG code"))?,
+                    width.parse::<f64>().map_err(|_| error("invalid width"))?,
+                ),
+                _ => return Err(error("DECAY needs a PDG code and a width")),
+            };
+            decays.push(SlhaDecay{ mcid, width, channels: Vec::new() });
+            in_decay = true;
+        } else if fields[0].eq_ignore_ascii_case("BLOCK") {
+            in_decay = false;
+        } else if in_decay {
+            let branching_ratio = fields[0].parse::<f64>().map_err(|_| error("invalid branching ratio"))?;
+            let nda = fields.get(1).and_then(|nda| nda.parse::<usize>().ok()).ok_or_else(|| error("invalid NDA"))?;
+            if fields.len() != nda + 2 {
+                return Err(error(&format!("expected {} daughters", nda)));
+            }
+            let products = fields[2..]
+                .iter()
+                .map(|id| id.parse::<i64>().map_err(|_| error("invalid PDG code")))
+                .collect::<Result<Vec<i64>, String>>()?;
+            decays.last_mut().unwrap().channels.push(SlhaChannel{ branching_ratio, products });
+        }
+    }
+    Ok(decays)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdgdb::ParticleDecay;
+
+    #[test]
+    fn test_parse_slha(){
+        let text = "\
+BLOCK MASS   # Mass spectrum
+        25     1.25090000E+02   # h0
+DECAY       421     1.60500000E-12   # D0
+#          BR         NDA      ID1       ID2
+     3.94700000E-02    2        -321       211   # D0 -> K- pi+
+     1.44000000E-01    3        -321       211       111
+decay 15 2.27e-12
+     1.0  2  -211  16
+";
+        let decays = parse_slha(text).unwrap();
+        assert_eq!(decays.len(), 2);
+        assert_eq!(decays[0].mcid, 421);
+        assert_eq!(decays[0].width, 1.605e-12);
+        assert_eq!(decays[0].channels[1], SlhaChannel{ branching_ratio: 0.144, products: vec![-321, 211, 111] });
+        assert_eq!(decays[1].channels, vec![SlhaChannel{ branching_ratio: 1.0, products: vec![-211, 16] }]);
+
+        assert!(parse_slha("DECAY 421 1e-12\n 0.5 3 211 -211\n").unwrap_err().starts_with("line 2"));
+        assert!(parse_slha("DECAY 421\n").is_err());
+    }
+
+    #[test]
+    fn test_decay_block_round_trip(){
+        let decay = ParticleDecay::test("S032.1", 0.03947, None);
+        let modes = vec![ExportMode{ decay: &decay, daughters: vec!["K-".to_string(), "pi+".to_string()] }];
+        let resolve = |name: &str| match name {
+            "K-" => Some(-321),
+            "pi+" => Some(211),
+            _ => None,
+        };
+        let block = decay_block(421, "D0", Some(1.605e-12), &modes, resolve);
+        assert_eq!(block.lines().nth(1).unwrap(), "DECAY       421   1.60500000e-12   # D0");
+        let decays = parse_slha(&block).unwrap();
+        assert_eq!(decays, vec![SlhaDecay{
+            mcid: 421,
+            width: 1.605e-12,
+            channels: vec![SlhaChannel{ branching_ratio: 0.03947, products: vec![-321, 211] }],
+        }]);
+    }
+}
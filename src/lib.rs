@@ -1,5 +1,7 @@
 pub mod pdgdb;
 pub mod cli;
+pub mod generator;
+pub mod interop;
 
 #[cfg(test)]
 mod tests {
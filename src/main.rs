@@ -1,11 +1,22 @@
-mod cli;
-mod pdgdb;
-
+use std::collections::HashSet;
 use std::env;
+use pdgQuery::{cli, generator, interop, pdgdb};
 use pdgdb::queries::decayQueries::DecayQuery;
 use pdgdb::queries::singleQueries::ParticleQuery;
-use cli::parser::{query_type_classifier, query_verify, QueryType};
-use cli::printer::{decay_print, single_particle_print};
+use cli::parser::{decay_tree_options, export_format, max_pull, name_convention, output_format, output_profile, output_units, query_type_classifier, query_verify, sample_options, decay_sides, section_filter, details_mode, citation_format, OutputFormat, QueryType};
+use cli::latex::{latex_decays, latex_particle};
+use cli::markdown::{markdown_decays, markdown_particle, markdown_report};
+use cli::html::{html_decays, html_document, html_particle, html_report};
+use cli::printer::{conservation_print, decay_print, decay_tree_print, set_output_profile, set_output_units, single_particle_print, details_print, slha_comparison_print, validation_print};
+use pdgdb::queries::decayTree::{DecayTreeBuilder, DecayTreeConfig};
+use generator::sampler::DecaySampler;
+use interop::{evtgen, export_modes, heppdt, pythia, root, slha, ExportFormat};
+use interop::compare::{compare_channels, is_failure, pdg_modes, pdg_width, FileChannel};
+use interop::decayFile::{channel_products, parse_decay_file, FileDecayChannel};
+use interop::citation::citations;
+use pdgdb::conservation::check_decay;
+use pdgdb::naming::{name_table, Convention};
+use pdgdb::{DecayChannel, Particle};
 
 fn main() {
     let _args: Vec<String> = env::args()
@@ -16,17 +27,63 @@ fn main() {
         .map(|s| s.to_string())
         .collect();
 
-    let args = _args.iter().map(|s| s.as_str()).collect::<Vec<&str>>();
+    let mut args = _args.iter().map(|s| s.as_str()).collect::<Vec<&str>>();
+    let options = (decay_tree_options(&mut args), sample_options(&mut args), name_convention(&mut args), output_format(&mut args));
+    let (tree_config, sample_config, convention, format) = match options {
+        (Ok(tree_config), Ok(sample_config), Ok(convention), Ok(format)) => (tree_config, sample_config, convention, format),
+        (Err(error), _, _, _) | (_, Err(error), _, _) | (_, _, Err(error), _) | (_, _, _, Err(error)) => {
+            println!("{}", error);
+            return;
+        }
+    };
+    set_output_profile(output_profile(&mut args, format));
+    match output_units(&mut args) {
+        Ok(units) => set_output_units(units),
+        Err(error) => {
+            println!("{}", error);
+            return;
+        }
+    }
+
+    let sections = section_filter(&mut args);
+    let details = details_mode(&mut args);
 
     let query_type = query_type_classifier(&args);
+    // the listings are printed below the text tables of a particle, other outputs would drop them silently
+    if details && (format != OutputFormat::Text || query_type != QueryType::SingleParticle) {
+        println!("--details is only available for a single particle in text format");
+        return;
+    }
     let single_query = ParticleQuery::new();
     let decay_query = DecayQuery::new();
     match query_type{
         QueryType::SingleParticle => {
             let query = &args[0];
             let particle = single_query.query(&query);
-            if let Some(particle) = particle{
-                single_particle_print(&particle);
+            if let Some(mut particle) = particle{
+                if let Err(error) = particle.retain_sections(&sections) {
+                    println!("{}", error);
+                    return;
+                }
+                match format {
+                    OutputFormat::Text => {
+                        single_particle_print(&particle);
+                        if details {
+                            for measurement in particle.measurements.iter().flatten() {
+                                let listings = measurement.node_id.as_deref().and_then(|node_id| single_query.listings(node_id).ok());
+                                if let Some(listings) = listings.filter(|listings| !listings.is_empty()) {
+                                    details_print(measurement, &listings);
+                                }
+                            }
+                        }
+                    },
+                    OutputFormat::Latex => println!("{}", latex_particle(&particle)),
+                    OutputFormat::Markdown => println!("{}", markdown_particle(&particle)),
+                    OutputFormat::Html => {
+                        let title = particle.name.clone().unwrap_or(query.to_string());
+                        println!("{}", html_document(&title, &[html_particle(&particle)]));
+                    },
+                }
             }
             else{
                 println!("Particles nor their alias not found");
@@ -38,7 +95,7 @@ fn main() {
                 .map(|pdgid| decay_query
                 .map_decay(pdgid).unwrap())
                 .collect::<Vec<_>>();
-            decay_print(&decay_channels);
+            print_decays(decay_channels, convention, format);
         },
         QueryType::ParentlessDecayExact | QueryType::ParentlessDecayPartial => {
             let pdgids = decay_query.get_decays_extensive(&args).unwrap();
@@ -46,7 +103,7 @@ fn main() {
                 .map(|pdgid| decay_query
                 .map_decay(pdgid).unwrap())
                 .collect::<Vec<_>>();
-            decay_print(&decay_channels);
+            print_decays(decay_channels, convention, format);
         },
         QueryType::DecayWildcard => {
             let pdgids = decay_query.get_decays_inclusive_with_parent(&args).unwrap();
@@ -54,7 +111,7 @@ fn main() {
                 .map(|pdgid| decay_query
                 .map_decay(pdgid).unwrap())
                 .collect::<Vec<_>>();
-            decay_print(&decay_channels);
+            print_decays(decay_channels, convention, format);
         }
         QueryType::ParentlessDecayWildcard => {
             let pdgids = decay_query.get_decays_inclusive(&args).unwrap();
@@ -62,9 +119,334 @@ fn main() {
                 .map(|pdgid| decay_query
                 .map_decay(pdgid).unwrap())
                 .collect::<Vec<_>>();
-            decay_print(&decay_channels);
+            print_decays(decay_channels, convention, format);
+        },
+        QueryType::DecayTree => {
+            let mut builder = DecayTreeBuilder::new(tree_config);
+            match builder.build(args[1]) {
+                Some(tree) => decay_tree_print(&tree),
+                None => println!("Particles nor their alias not found"),
+            }
+        },
+        QueryType::DecaySample => {
+            // every channel takes part in the sampling, without recursion only the parent is decayed
+            let config = DecayTreeConfig{
+                min_probability: 0.0,
+                max_depth: if sample_config.recursive { tree_config.max_depth } else { 1 },
+                ..tree_config
+            };
+            let mut builder = DecayTreeBuilder::new(config);
+            let tree = match builder.build(args[1]) {
+                Some(tree) => tree,
+                None => {
+                    println!("Particles nor their alias not found");
+                    return;
+                }
+            };
+            if tree.is_leaf() {
+                println!("No decay channel with a measured branching fraction for {}", args[1]);
+                return;
+            }
+            let mut sampler = DecaySampler::new(&tree, sample_config.seed);
+            match sample_config.momenta {
+                None => {
+                    for index in 0..sample_config.events {
+                        println!("{}", sampler.sample().to_json(index, args[1]));
+                    }
+                },
+                Some(format) => {
+                    println!("{}", format.header());
+                    for index in 0..sample_config.events {
+                        match sampler.generate_open() {
+                            Ok(event) => println!("{}", format.write_event(index, &event)),
+                            Err(error) => {
+                                eprintln!("Stopped after {} events: {}", index, error);
+                                break;
+                            },
+                        }
+                    }
+                    println!("{}", format.footer());
+                },
+            }
+            // keep stdout valid JSON lines
+            for (particle, fraction) in sampler.unassigned_fractions() {
+                eprintln!("Unassigned branching fraction of {}: {:.4}", particle, fraction);
+            }
+        },
+        QueryType::Export => {
+            let mut names = args[1..].to_vec();
+            match export_format(&mut names) {
+                Ok(format) => export(&names, format, &single_query, &decay_query),
+                Err(error) => println!("{}", error),
+            }
+        },
+        QueryType::ConservationCheck => {
+            let decay = &args[1..];
+            let (parent, daughters) = match decay_sides(decay) {
+                Ok(sides) => sides,
+                Err(error) => {
+                    println!("{}", error);
+                    return;
+                }
+            };
+            let mut particles = Vec::new();
+            for name in std::iter::once(parent).chain(daughters) {
+                match single_query.query(name) {
+                    Some(particle) => particles.push(particle),
+                    None => {
+                        println!("Particle {} nor its alias not found", name);
+                        return;
+                    }
+                }
+            }
+            let parent = particles.remove(0);
+            let report = check_decay(&parent, &particles);
+            conservation_print(&decay.join(" "), &report);
+        },
+        QueryType::Report => {
+            let mut particles = Vec::new();
+            for name in args[1..].iter() {
+                match single_query.query(name) {
+                    Some(particle) => particles.push(particle),
+                    None => eprintln!("Particle {} nor its alias not found, skipped", name),
+                }
+            }
+            match format {
+                OutputFormat::Html => println!("{}", html_report(&particles)),
+                OutputFormat::Latex => println!("{}", particles.iter().map(latex_particle).collect::<Vec<String>>().join("\n\n")),
+                // a report is a document, markdown unless asked otherwise
+                OutputFormat::Text | OutputFormat::Markdown => println!("{}", markdown_report(&particles)),
+            }
+        },
+        QueryType::Validate => {
+            let mut rest = args[1..].to_vec();
+            let max_pull = match max_pull(&mut rest) {
+                Ok(max_pull) => max_pull,
+                Err(error) => {
+                    println!("{}", error);
+                    std::process::exit(2);
+                }
+            };
+            match rest.as_slice() {
+                [path] => if !validate(path, max_pull, &single_query, &decay_query) {
+                    std::process::exit(1);
+                },
+                _ => {
+                    println!("Usage: pdgQuery validate <file> [--max-pull N]");
+                    std::process::exit(2);
+                },
+            }
+        },
+        QueryType::SlhaCompare => compare_slha(args[1], &single_query, &decay_query),
+        QueryType::Cite => {
+            let mut rest = args[1..].to_vec();
+            let citation_format = citation_format(&mut rest);
+            let node_id = match rest.as_slice() {
+                [node_id] => *node_id,
+                _ => {
+                    println!("Give one node id to cite, i.e., pdgQuery cite S035M");
+                    return;
+                }
+            };
+            // a particle name cites every value of the particle
+            let (node_id, whole_particle) = match single_query.query(node_id).and_then(|particle| particle.node_id) {
+                Some(particle_id) => (particle_id, true),
+                None => (node_id.to_string(), false),
+            };
+            match (single_query.edition(), single_query.references(&node_id, whole_particle)) {
+                (Ok(edition), Ok(references)) => println!("{}", citations(&edition, &references, citation_format)),
+                (Err(error), _) | (_, Err(error)) => println!("Cannot read the references of {}: {}", node_id, error),
+            }
         },
         QueryType::Unknown => panic!("Unknown query type, make for decay make sure you have double quote pdgQuery \"A -> B C D\" or for single particle pdgQuery \"A\""),
     }
 
 }
+
+// The documents render the PDG names themselves, the naming convention only applies to text
+fn print_decays(decay_channels: Vec<DecayChannel>, convention: Option<Convention>, format: OutputFormat) {
+    match (format, convention) {
+        (OutputFormat::Latex, _) => println!("{}", latex_decays(&decay_channels)),
+        (OutputFormat::Markdown, _) => println!("{}", markdown_decays(&decay_channels)),
+        (OutputFormat::Html, _) => println!("{}", html_decays(&decay_channels)),
+        (OutputFormat::Text, Some(convention)) => {
+            let renamed = decay_channels.iter().map(|channel| name_table().render_channel(channel, convention)).collect();
+            decay_print(&renamed);
+        },
+        (OutputFormat::Text, None) => decay_print(&decay_channels),
+    }
+}
+
+// Without names every particle with an MC ID is exported
+fn export(names: &[&str], format: ExportFormat, single_query: &ParticleQuery, decay_query: &DecayQuery) {
+    let names = if names.is_empty() {
+        single_query.all_names().unwrap_or_default()
+    } else {
+        names.iter().map(|name| name.to_string()).collect()
+    };
+    // Pythia and the tables describe a particle together with its antiparticle
+    let merge_antiparticles = format != ExportFormat::EvtGen;
+    let mut exported = HashSet::new();
+    let mut blocks = Vec::new();
+    let mut root_index = 0;
+    for name in names.iter() {
+        let mut particle = match single_query.query(name) {
+            Some(particle) => particle,
+            None => {
+                eprintln!("Particle {} nor its alias not found, skipped", name);
+                continue;
+            }
+        };
+        if let (true, Some(mcid)) = (merge_antiparticles, particle.mcid()) {
+            if mcid.0 < 0 {
+                if let Some(conjugate) = single_query.query(&(-mcid.0).to_string()) {
+                    particle = conjugate;
+                }
+            }
+            if !exported.insert(mcid.0.abs()) {
+                continue;
+            }
+        }
+        let decays = particle.decay.as_deref().unwrap_or_default();
+        let modes = export_modes(decay_query, decays);
+        let parent = particle.name.as_deref().unwrap_or(name);
+        let anti_name = particle.mcid()
+            .filter(|mcid| !mcid.is_self_conjugate())
+            .and_then(|mcid| single_query.get_name(mcid.antiparticle().0));
+        let resolve_mcid = |name: &str| single_query.get_mcid(name);
+        let entry = match format {
+            ExportFormat::EvtGen => Some(evtgen::decay_block(parent, &modes)),
+            ExportFormat::Pythia => pythia::particle_entry(&particle, anti_name.as_deref(), &modes, resolve_mcid),
+            ExportFormat::Root => {
+                let entry = root::root_entry(root_index, &particle, anti_name.as_deref(), &modes, resolve_mcid);
+                // skipped particles take no index, the table must stay contiguous
+                if entry.is_some() {
+                    root_index += if anti_name.is_some() { 2 } else { 1 };
+                }
+                entry
+            },
+            ExportFormat::HepPdt => Some(heppdt::heppdt_entry(&particle)),
+            ExportFormat::Slha => particle.mcid()
+                .map(|mcid| slha::decay_block(mcid.0, parent, particle.total_width(), &modes, resolve_mcid)),
+        };
+        match entry {
+            Some(entry) => blocks.push(entry),
+            None => eprintln!("Particle {} has no MC ID, skipped", name),
+        }
+    }
+    match format {
+        ExportFormat::EvtGen => println!("{}", evtgen::evtgen_file(&blocks)),
+        ExportFormat::Pythia => println!("{}", pythia::pythia_file(&blocks)),
+        ExportFormat::Root => println!("{}", root::root_file(&blocks)),
+        ExportFormat::HepPdt => println!("{}", heppdt::heppdt_file(&blocks)),
+        ExportFormat::Slha => println!("{}", slha::slha_file(&blocks)),
+    }
+}
+
+fn compare_slha(path: &str, single_query: &ParticleQuery, decay_query: &DecayQuery) {
+    let decays = match std::fs::read_to_string(path).map_err(|error| error.to_string()).and_then(|text| slha::parse_slha(&text)) {
+        Ok(decays) => decays,
+        Err(error) => {
+            println!("Cannot read SLHA file {}: {}", path, error);
+            return;
+        }
+    };
+    let name_of = |mcid: i64| single_query.get_name(mcid).unwrap_or(mcid.to_string());
+    for decay in decays.iter() {
+        let particle = match single_query.query(&decay.mcid.to_string()) {
+            Some(particle) => particle,
+            None => {
+                println!("Particle with MC ID {} not found, skipped", decay.mcid);
+                continue;
+            }
+        };
+        let decays = particle.decay.as_deref().unwrap_or_default();
+        let modes = pdg_modes(&export_modes(decay_query, decays), |name| single_query.get_mcid(name));
+        let channels = decay.channels
+            .iter()
+            .map(|channel| FileChannel{
+                products: channel.products.clone(),
+                branching_ratio: channel.branching_ratio,
+                label: channel.products.iter().map(|&id| name_of(id)).collect::<Vec<String>>().join(" "),
+            })
+            .collect::<Vec<FileChannel>>();
+        let comparisons = compare_channels(&channels, &modes);
+        let parent = particle.name.clone().unwrap_or(name_of(decay.mcid));
+        slha_comparison_print(&parent, decay.width, pdg_width(&particle), &comparisons);
+    }
+}
+
+// Returns false when any channel fails, so CI can use the exit code
+fn validate(path: &str, max_pull: f64, single_query: &ParticleQuery, decay_query: &DecayQuery) -> bool {
+    let file = match std::fs::read_to_string(path).map_err(|error| error.to_string()).and_then(|text| parse_decay_file(&text)) {
+        Ok(file) => file,
+        Err(error) => {
+            println!("Cannot read decay file {}: {}", path, error);
+            return false;
+        }
+    };
+    // EvtGen names or Pythia MC IDs
+    let resolve_mcid = |name: &str| name.parse::<i64>().ok().or_else(|| single_query.get_mcid(&evtgen::pdg_name(name)));
+    let mut failures = 0;
+    for decay in file.decays.iter() {
+        match resolve_mcid(&decay.parent).and_then(|mcid| single_query.query(&mcid.to_string())) {
+            Some(particle) => failures += validate_decay(&particle, &decay.channels, false, max_pull, single_query, decay_query, resolve_mcid),
+            None => {
+                println!("line {}: parent {} not found in the PDG", decay.line, decay.parent);
+                failures += 1;
+            }
+        }
+    }
+    // a CDecay validates the channels of its charge conjugate Decay block, conjugated
+    for conjugate in file.conjugate_decays.iter() {
+        let (mcid, particle) = match resolve_mcid(&conjugate.parent).and_then(|mcid| Some((mcid, single_query.query(&mcid.to_string())?))) {
+            Some(found) => found,
+            None => {
+                println!("line {}: CDecay parent {} not found in the PDG", conjugate.line, conjugate.parent);
+                failures += 1;
+                continue;
+            }
+        };
+        let anti_mcid = pdgdb::mcid::McId(mcid).antiparticle().0;
+        match file.decays.iter().find(|decay| resolve_mcid(&decay.parent) == Some(anti_mcid)) {
+            Some(decay) => failures += validate_decay(&particle, &decay.channels, true, max_pull, single_query, decay_query, resolve_mcid),
+            None => {
+                println!("line {}: CDecay {} has no Decay of its charge conjugate", conjugate.line, conjugate.parent);
+                failures += 1;
+            }
+        }
+    }
+    println!("{} decays validated, {} failures", file.decays.len() + file.conjugate_decays.len(), failures);
+    failures == 0
+}
+
+// Compares the channels of one decay file block with the PDG modes of the parent and returns the failures.
+// With conjugate, the channels belong to the charge conjugate parent and their products are conjugated.
+fn validate_decay<F>(particle: &Particle, channels: &[FileDecayChannel], conjugate: bool, max_pull: f64, single_query: &ParticleQuery, decay_query: &DecayQuery, resolve_mcid: F) -> usize
+where
+    F: Fn(&str) -> Option<i64> + Copy,
+{
+    let decays = particle.decay.as_deref().unwrap_or_default();
+    let modes = pdg_modes(&export_modes(decay_query, decays), |name| single_query.get_mcid(name));
+    let channels = channels
+        .iter()
+        .map(|channel| {
+            // a channel with an unknown daughter matches no PDG mode and fails
+            let (products, label) = match channel_products(channel, resolve_mcid) {
+                Ok(products) => {
+                    let products = match conjugate {
+                        true => products.iter().map(|&id| pdgdb::mcid::McId(id).antiparticle().0).collect::<Vec<i64>>(),
+                        false => products,
+                    };
+                    let label = products.iter().map(|&id| single_query.get_name(id).unwrap_or(id.to_string())).collect::<Vec<String>>().join(" ");
+                    (products, label)
+                },
+                Err(error) => (Vec::new(), format!("line {}: {} ({})", channel.line, channel.daughters.join(" "), error)),
+            };
+            FileChannel{ products, branching_ratio: channel.branching_ratio, label }
+        })
+        .collect::<Vec<FileChannel>>();
+    let comparisons = compare_channels(&channels, &modes);
+    validation_print(particle.name.as_deref().unwrap_or_default(), &comparisons, max_pull);
+    comparisons.iter().filter(|comparison| is_failure(comparison, max_pull)).count()
+}
@@ -1,5 +1,8 @@
 use rusqlite::Connection;
 use dotenv::from_path;
+use std::ops::Deref;
+use std::sync::Arc;
+use super::pdgDoc::PdgDoc;
 
 pub fn connect() -> Result<Connection, Box<dyn std::error::Error>> {
     let cargo_dir = env!("CARGO_MANIFEST_DIR");
@@ -11,6 +14,32 @@ pub fn connect() -> Result<Connection, Box<dyn std::error::Error>> {
     Ok(conn)
 }
 
+// The database with the documentation of its codes, every query goes through one
+pub struct PdgConnection{
+    conn: Connection,
+    doc: Arc<PdgDoc>,
+}
+
+impl PdgConnection{
+    pub fn open() -> Result<PdgConnection, Box<dyn std::error::Error>> {
+        let conn = connect()?;
+        let doc = Arc::new(PdgDoc::load(&conn));
+        Ok(PdgConnection{ conn, doc })
+    }
+
+    pub fn doc(&self) -> &Arc<PdgDoc> {
+        &self.doc
+    }
+}
+
+impl Deref for PdgConnection{
+    type Target = Connection;
+
+    fn deref(&self) -> &Connection {
+        &self.conn
+    }
+}
+
 #[cfg(test)]
 mod test{
 
@@ -0,0 +1,185 @@
+use crate::pdgdb::mcid::McId;
+use crate::pdgdb::Particle;
+
+// Additive quantum numbers of a single particle, None when the database does not give enough information
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct QuantumNumbers
+{
+    pub charge: Option<f64>, // from pdgparticle.charge
+    pub baryon_number: Option<f64>,
+    pub lepton_e: Option<f64>,
+    pub lepton_mu: Option<f64>,
+    pub lepton_tau: Option<f64>,
+    pub strangeness: Option<f64>,
+    pub charm: Option<f64>,
+    pub beauty: Option<f64>,
+    pub mass: Option<f64>, // GeV, from the mass summary value
+}
+
+impl QuantumNumbers{
+    pub fn from_particle(particle: &Particle) -> Self{
+        let mcid = particle.pdgid.map(McId);
+        let leptons = mcid.map(|id| id.lepton_numbers());
+        QuantumNumbers{
+            charge: particle.charge,
+            baryon_number: mcid.map(|id| id.baryon_number() as f64),
+            lepton_e: leptons.map(|l| l[0] as f64),
+            lepton_mu: leptons.map(|l| l[1] as f64),
+            lepton_tau: leptons.map(|l| l[2] as f64),
+            strangeness: mcid.and_then(|id| id.strangeness()).map(|s| s as f64),
+            charm: mcid.and_then(|id| id.charm()).map(|c| c as f64),
+            beauty: mcid.and_then(|id| id.beauty()).map(|b| b as f64),
+            mass: particle.mass,
+        }
+    }
+
+    // Sum over a set of particles, a quantity is unknown as soon as one particle lacks it
+    fn sum(numbers: &[QuantumNumbers]) -> Self{
+        let total = |get: fn(&QuantumNumbers) -> Option<f64>| -> Option<f64> {
+            numbers.iter().map(get).sum()
+        };
+        QuantumNumbers{
+            charge: total(|n| n.charge),
+            baryon_number: total(|n| n.baryon_number),
+            lepton_e: total(|n| n.lepton_e),
+            lepton_mu: total(|n| n.lepton_mu),
+            lepton_tau: total(|n| n.lepton_tau),
+            strangeness: total(|n| n.strangeness),
+            charm: total(|n| n.charm),
+            beauty: total(|n| n.beauty),
+            mass: total(|n| n.mass),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Verdict{
+    Conserved,
+    Violated,
+    Undetermined,
+}
+
+#[derive(Debug)]
+pub struct ConservationCheck{
+    pub quantity: &'static str,
+    pub initial: Option<f64>,
+    pub final_state: Option<f64>,
+    pub verdict: Verdict,
+    // strangeness, charm and beauty are only conserved by the strong and electromagnetic interactions
+    pub weak_allowed: bool,
+}
+
+#[derive(Debug)]
+pub struct ConservationReport{
+    pub checks: Vec<ConservationCheck>,
+    pub parent_mass: Option<f64>,
+    pub daughter_mass_sum: Option<f64>,
+    pub kinematically_allowed: Option<bool>,
+}
+
+impl ConservationReport{
+    pub fn is_allowed(&self) -> bool{
+        self.checks.iter().all(|check| check.verdict != Verdict::Violated || check.weak_allowed)
+            && self.kinematically_allowed != Some(false)
+    }
+}
+
+pub fn check_decay(parent: &Particle, daughters: &[Particle]) -> ConservationReport{
+    let initial = QuantumNumbers::from_particle(parent);
+    let final_state = QuantumNumbers::sum(
+        &daughters.iter().map(QuantumNumbers::from_particle).collect::<Vec<_>>()
+    );
+
+    let compare = |quantity: &'static str, initial: Option<f64>, final_state: Option<f64>, weak_allowed: bool| {
+        let verdict = match (initial, final_state) {
+            (Some(i), Some(f)) if (i - f).abs() < 1e-6 => Verdict::Conserved,
+            (Some(_), Some(_)) => Verdict::Violated,
+            _ => Verdict::Undetermined,
+        };
+        ConservationCheck{ quantity, initial, final_state, verdict, weak_allowed }
+    };
+
+    let checks = vec![
+        compare("Electric charge", initial.charge, final_state.charge, false),
+        compare("Baryon number", initial.baryon_number, final_state.baryon_number, false),
+        compare("Lepton number L_e", initial.lepton_e, final_state.lepton_e, false),
+        compare("Lepton number L_mu", initial.lepton_mu, final_state.lepton_mu, false),
+        compare("Lepton number L_tau", initial.lepton_tau, final_state.lepton_tau, false),
+        compare("Strangeness", initial.strangeness, final_state.strangeness, true),
+        compare("Charm", initial.charm, final_state.charm, true),
+        compare("Beauty", initial.beauty, final_state.beauty, true),
+    ];
+
+    let kinematically_allowed = match (initial.mass, final_state.mass) {
+        (Some(parent_mass), Some(daughter_mass_sum)) => Some(parent_mass >= daughter_mass_sum),
+        _ => None,
+    };
+
+    ConservationReport{
+        checks,
+        parent_mass: initial.mass,
+        daughter_mass_sum: final_state.mass,
+        kinematically_allowed,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn particle(name: &str, mcid: i64, charge: f64, mass: f64) -> Particle{
+        Particle{
+            name: Some(name.to_string()),
+            pdgid: Some(mcid),
+            charge: Some(charge),
+            mass: Some(mass),
+            ..Default::default()
+        }
+    }
+
+    fn verdict_of(report: &ConservationReport, quantity: &str) -> Verdict{
+        report.checks.iter().find(|check| check.quantity == quantity).unwrap().verdict
+    }
+
+    #[test]
+    fn test_lepton_flavour_violation(){
+        let tau = particle("tau-", 15, -1.0, 1.77686);
+        let daughters = vec![particle("mu-", 13, -1.0, 0.10566), particle("gamma", 22, 0.0, 0.0)];
+        let report = check_decay(&tau, &daughters);
+        assert_eq!(verdict_of(&report, "Electric charge"), Verdict::Conserved);
+        assert_eq!(verdict_of(&report, "Lepton number L_mu"), Verdict::Violated);
+        assert_eq!(verdict_of(&report, "Lepton number L_tau"), Verdict::Violated);
+        assert_eq!(report.kinematically_allowed, Some(true));
+        assert!(!report.is_allowed());
+    }
+
+    #[test]
+    fn test_weak_decay_allowed(){
+        let kaon = particle("K+", 321, 1.0, 0.493677);
+        let daughters = vec![particle("mu+", -13, 1.0, 0.10566), particle("nu_mu", 14, 0.0, 0.0)];
+        let report = check_decay(&kaon, &daughters);
+        assert_eq!(verdict_of(&report, "Strangeness"), Verdict::Violated);
+        assert_eq!(verdict_of(&report, "Lepton number L_mu"), Verdict::Conserved);
+        assert!(report.is_allowed());
+    }
+
+    #[test]
+    fn test_kinematically_forbidden(){
+        let pion = particle("pi0", 111, 0.0, 0.1349768);
+        let daughters = vec![particle("mu+", -13, 1.0, 0.10566), particle("mu-", 13, -1.0, 0.10566)];
+        let report = check_decay(&pion, &daughters);
+        assert_eq!(report.kinematically_allowed, Some(false));
+        assert!(!report.is_allowed());
+    }
+
+    #[test]
+    fn test_unknown_quantities(){
+        let mut kaon = particle("K_S0", 310, 0.0, 0.497611);
+        let daughters = vec![particle("pi+", 211, 1.0, 0.13957), particle("pi-", -211, -1.0, 0.13957)];
+        let report = check_decay(&kaon, &daughters);
+        assert_eq!(verdict_of(&report, "Strangeness"), Verdict::Undetermined);
+        kaon.mass = None;
+        let report = check_decay(&kaon, &daughters);
+        assert_eq!(report.kinematically_allowed, None);
+    }
+}
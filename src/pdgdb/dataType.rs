@@ -0,0 +1,137 @@
+// pdgid.data_type decoded, the codes without a variant keep the meaning the pdgdoc table gives them,
+// i.e., magnetic moments, form factors or ratios
+use super::pdgDoc::PdgDoc;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DataType{
+    Mass,              // M
+    Width,             // G
+    Lifetime,          // T
+    ExclusiveFraction, // BFX
+    InclusiveFraction, // BFI
+    Other{ code: String, description: Option<String> },
+}
+
+impl Default for DataType{
+    fn default() -> DataType{
+        DataType::Other{ code: String::new(), description: None }
+    }
+}
+
+impl DataType{
+    // The data types every particle may have, the others come from pdgdoc
+    pub const KNOWN: [DataType; 5] = [
+        DataType::Mass,
+        DataType::Width,
+        DataType::Lifetime,
+        DataType::ExclusiveFraction,
+        DataType::InclusiveFraction,
+    ];
+
+    pub fn decode(code: &str, doc: &PdgDoc) -> DataType{
+        match code {
+            "M" => DataType::Mass,
+            "G" => DataType::Width,
+            "T" => DataType::Lifetime,
+            "BFX" => DataType::ExclusiveFraction,
+            "BFI" => DataType::InclusiveFraction,
+            _ => DataType::Other{ code: code.to_string(), description: doc.meaning("pdgid", "data_type", code).map(|description| description.to_string()) },
+        }
+    }
+
+    pub fn code(&self) -> &str{
+        match self {
+            DataType::Mass => "M",
+            DataType::Width => "G",
+            DataType::Lifetime => "T",
+            DataType::ExclusiveFraction => "BFX",
+            DataType::InclusiveFraction => "BFI",
+            DataType::Other{ code, .. } => code,
+        }
+    }
+
+    // Title of the section of the measurement table
+    pub fn section(&self) -> String{
+        match self {
+            DataType::Mass => "Mass".to_string(),
+            DataType::Width => "Width".to_string(),
+            DataType::Lifetime => "Lifetime".to_string(),
+            DataType::ExclusiveFraction | DataType::InclusiveFraction => "Branching fractions".to_string(),
+            DataType::Other{ description: Some(description), .. } => description.clone(),
+            DataType::Other{ code, .. } if code.is_empty() => "Other".to_string(),
+            DataType::Other{ code, .. } => code.clone(),
+        }
+    }
+
+    // Names of --only in lower case, singular and plural, the code, and for the others the pdgdoc description
+    // and its words
+    pub fn aliases(&self) -> Vec<String>{
+        let aliases: &[&str] = match self {
+            DataType::Mass => &["mass", "masses"],
+            DataType::Width => &["width", "widths"],
+            DataType::Lifetime => &["lifetime", "lifetimes"],
+            DataType::ExclusiveFraction | DataType::InclusiveFraction => &["fraction", "fractions", "branching fraction", "branching fractions"],
+            DataType::Other{ description, .. } => {
+                let description = description.as_deref().unwrap_or("").to_lowercase();
+                let words = description.split_whitespace().map(|word| word.to_string()).collect::<Vec<String>>();
+                let mut aliases = std::iter::once(description.clone()).chain(words).collect::<Vec<String>>();
+                aliases.push(self.code().to_lowercase());
+                aliases.retain(|alias| !alias.is_empty());
+                aliases.dedup();
+                return aliases;
+            },
+        };
+        aliases.iter().map(|alias| alias.to_string()).chain(std::iter::once(self.code().to_lowercase())).collect()
+    }
+
+    // A filter of --only is one of the aliases, in any case
+    pub fn matches(&self, filter: &str) -> bool{
+        let filter = filter.trim().to_lowercase();
+        !filter.is_empty() && self.aliases().contains(&filter)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn moment_doc() -> PdgDoc{
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE pdgdoc (id INTEGER, table_name TEXT, column_name TEXT, value TEXT, indicator TEXT, description TEXT, comment TEXT);
+             INSERT INTO pdgdoc VALUES (1, 'PDGID', 'DATA_TYPE', 'MM', NULL, 'Magnetic moment', NULL);",
+        ).unwrap();
+        PdgDoc::load(&conn)
+    }
+
+    #[test]
+    fn test_decode(){
+        let doc = moment_doc();
+        assert_eq!(DataType::decode("M", &doc), DataType::Mass);
+        assert_eq!(DataType::decode("BFI", &doc).section(), "Branching fractions");
+        let moment = DataType::decode("MM", &doc);
+        assert_eq!(moment.section(), "Magnetic moment");
+        assert_eq!(moment.code(), "MM");
+        assert_eq!(DataType::decode("XYZ", &doc).section(), "XYZ");
+    }
+
+    #[test]
+    fn test_matches(){
+        let doc = moment_doc();
+        assert!(DataType::Mass.matches("mass"));
+        assert!(DataType::Lifetime.matches("Lifetime"));
+        assert!(DataType::ExclusiveFraction.matches("fraction"));
+        assert!(DataType::InclusiveFraction.matches("Branching Fractions"));
+        assert!(!DataType::Mass.matches("width"));
+        // plurals are listed, not guessed by cutting an "s"
+        assert!(DataType::Mass.matches("masses"));
+        assert!(!DataType::Mass.matches("masse"));
+        assert!(!DataType::Width.matches("widthss"));
+        assert!(DataType::Width.matches("g"));
+        assert!(DataType::decode("MM", &doc).matches("magnetic"));
+        assert!(DataType::decode("MM", &doc).matches("mm"));
+        assert!(DataType::decode("MM", &doc).matches("magnetic moment"));
+        assert_eq!(DataType::decode("MM", &doc).aliases(), vec!["magnetic moment", "magnetic", "moment", "mm"]);
+        assert!(!DataType::decode("MM", &doc).matches(""));
+    }
+}
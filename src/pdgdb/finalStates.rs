@@ -0,0 +1,251 @@
+use std::collections::{BTreeMap, HashMap};
+use std::hash::Hash;
+
+use crate::pdgdb::measured::Measured;
+use crate::pdgdb::queries::decayTree::DecayNode;
+
+// One branching fraction used on the way from the root to a final state
+#[derive(Debug, Clone)]
+pub struct PathFactor{
+    pub node_id: String,
+    pub fraction: Measured,
+}
+
+// A set of stable particles reached through one path of the decay tree
+#[derive(Debug, Clone)]
+pub struct FinalState{
+    pub particles: BTreeMap<String, u16>,
+    pub truncated: BTreeMap<String, u16>, // unstable particles the tree did not decay, the state is then incomplete
+    pub charged_multiplicity: u16,
+    pub probability: f64, // product of the branching fractions in factors
+    pub factors: Vec<PathFactor>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct InclusiveFraction{
+    pub fraction: Measured,
+    pub paths: usize, // number of final states that contributed
+}
+
+impl FinalState{
+    fn single(node: &DecayNode) -> Self{
+        let is_charged = node.charge.is_some_and(|charge| charge != 0.0);
+        FinalState{
+            particles: BTreeMap::from([(node.particle.clone(), 1)]),
+            truncated: BTreeMap::new(),
+            charged_multiplicity: is_charged as u16,
+            probability: 1.0,
+            factors: Vec::new(),
+        }
+    }
+
+    // A particle whose decays were cut, its products and their charges are unknown
+    fn truncated(node: &DecayNode) -> Self{
+        FinalState{
+            particles: BTreeMap::new(),
+            truncated: BTreeMap::from([(node.particle.clone(), 1)]),
+            charged_multiplicity: 0,
+            probability: 1.0,
+            factors: Vec::new(),
+        }
+    }
+
+    fn combine(&self, other: &FinalState) -> Self{
+        let mut combined = self.clone();
+        for (name, count) in other.particles.iter() {
+            *combined.particles.entry(name.clone()).or_insert(0) += count;
+        }
+        for (name, count) in other.truncated.iter() {
+            *combined.truncated.entry(name.clone()).or_insert(0) += count;
+        }
+        combined.charged_multiplicity += other.charged_multiplicity;
+        combined.probability *= other.probability;
+        combined.factors.extend(other.factors.iter().cloned());
+        combined
+    }
+
+    pub fn is_complete(&self) -> bool{
+        self.truncated.is_empty()
+    }
+
+    pub fn count(&self, name: &str) -> u16{
+        self.particles.get(name).copied().unwrap_or(0)
+    }
+
+    // Number of particles whose name satisfies the predicate, i.e., all charged kaons with |name| name == "K+" || name == "K-"
+    pub fn count_matching<F: Fn(&str) -> bool>(&self, predicate: F) -> u16{
+        self.particles
+            .iter()
+            .filter(|(name, _)| predicate(name))
+            .map(|(_, count)| count)
+            .sum()
+    }
+
+    pub fn total_multiplicity(&self) -> u16{
+        self.particles.values().sum()
+    }
+}
+
+// Expand the tree into every combination of stable particles, the probabilities are relative to the root.
+// Paths through a truncated particle give incomplete states, they are no final states of stable particles
+pub fn final_states(node: &DecayNode) -> Vec<FinalState>{
+    if node.truncated {
+        return vec![FinalState::truncated(node)];
+    }
+    if node.is_stable() {
+        return vec![FinalState::single(node)];
+    }
+    let mut states = Vec::new();
+    for channel in node.channels.iter() {
+        let factor = PathFactor{
+            node_id: channel.node_id.clone(),
+            fraction: Measured::central(channel.branching_fraction, channel.plus_error, channel.minus_error),
+        };
+        let mut channel_states = vec![FinalState{
+            particles: BTreeMap::new(),
+            truncated: BTreeMap::new(),
+            charged_multiplicity: 0,
+            probability: channel.branching_fraction,
+            factors: vec![factor],
+        }];
+        for daughter in channel.daughters.iter() {
+            let daughter_states = final_states(daughter);
+            channel_states = channel_states
+                .iter()
+                .flat_map(|state| daughter_states.iter().map(move |daughter_state| state.combine(daughter_state)))
+                .collect();
+        }
+        states.extend(channel_states);
+    }
+    states
+}
+
+// Sum of the probabilities of the selected final states.
+// The errors are linearised in every distinct branching fraction, treating different modes as uncorrelated,
+// so a mode that appears in several paths contributes coherently. Each side keeps its own error.
+pub fn inclusive_fraction<F: Fn(&FinalState) -> bool>(states: &[FinalState], predicate: F) -> InclusiveFraction{
+    let selected = states.iter().filter(|state| predicate(state)).collect::<Vec<_>>();
+    let value = selected.iter().map(|state| state.probability).sum();
+
+    // derivative of the sum with respect to each branching fraction
+    let mut gradients: HashMap<&str, (f64, Measured)> = HashMap::new();
+    for state in selected.iter() {
+        for factor in state.factors.iter() {
+            if factor.fraction.value == 0.0 {
+                continue;
+            }
+            let gradient = gradients.entry(factor.node_id.as_str()).or_insert((0.0, factor.fraction));
+            gradient.0 += state.probability / factor.fraction.value;
+        }
+    }
+    // one unknown error leaves the side unknown
+    let error = |side: fn(&Measured) -> Option<f64>| gradients
+        .values()
+        .map(|(gradient, fraction)| side(fraction).map(|error| (gradient * error).powi(2)))
+        .sum::<Option<f64>>()
+        .map(f64::sqrt);
+
+    InclusiveFraction{
+        fraction: Measured::central(value, error(|fraction| fraction.plus_error), error(|fraction| fraction.minus_error)),
+        paths: selected.len(),
+    }
+}
+
+// Group the final states by a key, i.e., the charged multiplicity, and sum each group
+pub fn group_final_states<K, F>(states: &[FinalState], key: F) -> Vec<(K, InclusiveFraction)>
+where
+    K: Eq + Hash + Ord + Clone,
+    F: Fn(&FinalState) -> K,
+{
+    let mut keys = states.iter().map(&key).collect::<Vec<K>>();
+    keys.sort();
+    keys.dedup();
+    keys.into_iter()
+        .map(|group| {
+            let fraction = inclusive_fraction(states, |state| key(state) == group);
+            (group, fraction)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdgdb::queries::decayTree::DecayBranch;
+
+    fn branch(node_id: &str, branching_fraction: f64, error: f64, daughters: Vec<DecayNode>) -> DecayBranch{
+        DecayBranch{
+            node_id: node_id.to_string(),
+            branching_fraction,
+            plus_error: Some(error),
+            minus_error: Some(error),
+            daughters,
+        }
+    }
+
+    fn leaf(name: &str, charge: f64) -> DecayNode{
+        DecayNode::leaf(name, None, Some(charge), 0.0)
+    }
+
+    // D*+ -> D0 pi+ (0.6) or D+ pi0 (0.3), D0 -> K- pi+ (0.04) or K- pi+ pi0 (0.14)
+    fn dstar_tree() -> DecayNode{
+        let mut d0 = leaf("D0", 0.0);
+        d0.channels.push(branch("D0.1", 0.04, 0.001, vec![leaf("K-", -1.0), leaf("pi+", 1.0)]));
+        d0.channels.push(branch("D0.2", 0.14, 0.005, vec![leaf("K-", -1.0), leaf("pi+", 1.0), leaf("pi0", 0.0)]));
+        let mut dstar = leaf("D*(2010)+", 1.0);
+        dstar.channels.push(branch("Dstar.1", 0.6, 0.01, vec![d0, leaf("pi+", 1.0)]));
+        dstar.channels.push(branch("Dstar.2", 0.3, 0.01, vec![leaf("D+", 1.0), leaf("pi0", 0.0)]));
+        dstar
+    }
+
+    #[test]
+    fn test_final_states(){
+        let states = final_states(&dstar_tree());
+        assert_eq!(states.len(), 3);
+        assert_eq!(states[0].count("pi+"), 2);
+        assert_eq!(states[0].charged_multiplicity, 3);
+        assert!((states[0].probability - 0.024).abs() < 1e-12);
+        assert_eq!(states[1].total_multiplicity(), 4);
+        assert_eq!(states[2].charged_multiplicity, 1);
+    }
+
+    #[test]
+    fn test_inclusive_fraction(){
+        let states = final_states(&dstar_tree());
+        let one_kaon = inclusive_fraction(&states, |state| state.count_matching(|name| name == "K-" || name == "K+") == 1);
+        assert_eq!(one_kaon.paths, 2);
+        assert!((one_kaon.fraction.value - 0.108).abs() < 1e-12);
+        // d/dB(D*) = 0.18, d/dB(D0.1) = d/dB(D0.2) = 0.6
+        let expected = ((0.18_f64 * 0.01).powi(2) + (0.6_f64 * 0.001).powi(2) + (0.6_f64 * 0.005).powi(2)).sqrt();
+        assert!((one_kaon.fraction.plus_error.unwrap() - expected).abs() < 1e-12);
+        assert!((one_kaon.fraction.minus_error.unwrap() - expected).abs() < 1e-12);
+
+        let nothing = inclusive_fraction(&states, |state| state.count("p") > 0);
+        assert_eq!(nothing, InclusiveFraction{ fraction: Measured::exact(0.0), paths: 0 });
+    }
+
+    #[test]
+    fn test_truncated_states(){
+        // D0 left undecayed by the depth limit
+        let mut tree = dstar_tree();
+        tree.channels[0].daughters[0].channels.clear();
+        tree.channels[0].daughters[0].truncated = true;
+        let states = final_states(&tree);
+        assert_eq!(states.len(), 2);
+        assert!(!states[0].is_complete());
+        assert_eq!(states[0].count("D0"), 0);
+        assert_eq!(states[0].truncated.get("D0"), Some(&1));
+        assert_eq!(states[0].count("pi+"), 1);
+        assert!(states[1].is_complete());
+        let incomplete = inclusive_fraction(&states, |state| !state.is_complete());
+        assert!((incomplete.fraction.value - 0.6).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_group_by_charged_multiplicity(){
+        let states = final_states(&dstar_tree());
+        let groups = group_final_states(&states, |state| state.charged_multiplicity);
+        assert_eq!(groups.iter().map(|(prongs, _)| *prongs).collect::<Vec<u16>>(), vec![1, 3]);
+        assert!((groups[1].1.fraction.value - 0.108).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,77 @@
+// Footnotes of the PDG values, i.e., how a fit was done or what a limit assumes. pdgfootnote links every footnote
+// to the pdgid of its value.
+use rusqlite::Result;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Footnote{
+    pub id: i64, // id in pdgfootnote
+    pub node_id: String, // pdgid of the value, i.e., "S035M"
+    pub index: Option<i64>, // footnote_index, the order of the footnotes of a value
+    pub text: String,
+}
+
+fn query_footnotes(conn: &rusqlite::Connection, node_id_pattern: &str) -> Result<Vec<Footnote>>{
+    let mut stmt = conn.prepare(
+        r#"
+        SELECT
+            id,
+            pdgid,
+            footnote_index,
+            text
+        FROM
+            pdgfootnote
+        WHERE
+            pdgid LIKE ?1
+        AND
+            text IS NOT NULL
+        ORDER BY
+            pdgid, footnote_index, id
+        "#,
+    )?;
+    let footnotes = stmt.query_map([node_id_pattern], |row| {
+        Ok(Footnote{
+            id: row.get("id")?,
+            node_id: row.get("pdgid")?,
+            index: row.get("footnote_index")?,
+            text: row.get("text")?,
+        })
+    })?.collect::<Result<Vec<Footnote>>>()?;
+    Ok(footnotes)
+}
+
+// Footnotes of the values whose pdgid matches the LIKE pattern, none for databases without pdgfootnote
+pub fn find_footnotes(conn: &rusqlite::Connection, node_id_pattern: &str) -> Vec<Footnote>{
+    query_footnotes(conn, node_id_pattern).unwrap_or_default()
+}
+
+// Ids of the footnotes of one value, in their order
+pub fn footnote_ids(footnotes: &[Footnote], node_id: Option<&str>) -> Vec<i64>{
+    footnotes
+        .iter()
+        .filter(|footnote| Some(footnote.node_id.as_str()) == node_id)
+        .map(|footnote| footnote.id)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_footnotes(){
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        assert!(find_footnotes(&conn, "S035%").is_empty());
+        conn.execute_batch(
+            "CREATE TABLE pdgfootnote (id INTEGER, pdgid TEXT, text TEXT, footnote_index INTEGER, changebar INTEGER);
+             INSERT INTO pdgfootnote VALUES (7, 'S035M', 'From a fit to the threshold scans.', 2, 0);
+             INSERT INTO pdgfootnote VALUES (5, 'S035M', 'Assumes CPT invariance.', 1, 0);
+             INSERT INTO pdgfootnote VALUES (9, 'S035.1', 'Not independent of the other modes.', 1, 0);
+             INSERT INTO pdgfootnote VALUES (11, 'S003M', 'Of the electron.', 1, 0);",
+        ).unwrap();
+        let footnotes = find_footnotes(&conn, "S035%");
+        assert_eq!(footnotes.iter().map(|footnote| footnote.id).collect::<Vec<i64>>(), vec![9, 5, 7]);
+        assert_eq!(footnote_ids(&footnotes, Some("S035M")), vec![5, 7]);
+        assert_eq!(footnote_ids(&footnotes, Some("S035.1")), vec![9]);
+        assert!(footnote_ids(&footnotes, None).is_empty());
+    }
+}
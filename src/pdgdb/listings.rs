@@ -0,0 +1,222 @@
+// The individual measurements of the PDG listings behind a summary value, from pdgmeasurement with its values in
+// pdgmeasurement_values and its paper in pdgreference. The PDG averages the measurements used_in_average with
+// weights 1/σ² and scales the error by S = sqrt(χ²/(N-1)) when S > 1.
+use rusqlite::Result;
+use super::measured::{Kind, Measured};
+use super::quantity::Unit;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Listing{
+    pub measurement_id: i64, // id in pdgmeasurement
+    pub document_id: Option<String>, // document_id in pdgreference, first author and year, i.e., "AAIJ 2021A"
+    pub year: Option<i64>, // publication_year in pdgreference
+    pub value: Option<f64>, // value in pdgmeasurement_values
+    pub plus_error: Option<f64>, // error_positive in pdgmeasurement_values
+    pub minus_error: Option<f64>, // error_negative in pdgmeasurement_values
+    pub limit_type: Option<String>, // limit_type in pdgmeasurement_values
+    pub confidence_level: Option<f64>, // confidence_level in pdgmeasurement
+    pub unit_text: Option<String>, // unit_text in pdgmeasurement_values
+    pub technique: Option<String>, // technique in pdgmeasurement, the experiment or method, i.e., "LHCB" or "BES3"
+    pub comment: Option<String>, // comment in pdgmeasurement
+    pub used_in_average: bool, // used_in_average in pdgmeasurement_values
+}
+
+impl Listing{
+    pub fn measured(&self) -> Option<Measured>{
+        Measured::from_pdgdata(self.value, self.plus_error, self.minus_error, self.limit_type.as_deref(), self.confidence_level)
+    }
+
+    // The listing in the unit of the summary value, i.e., MeV to GeV, unchanged when either unit does not parse
+    pub fn in_unit(&self, unit_text: Option<&str>) -> Listing{
+        let units = Unit::parse(self.unit_text.as_deref().unwrap_or("")).zip(unit_text.and_then(Unit::parse));
+        let (from, to) = match units {
+            Some((from, to)) if from != to && from.dimension == to.dimension && from.power == to.power => (from, to),
+            _ => return self.clone(),
+        };
+        let convert = |x: Option<f64>| x.and_then(|x| from.convert(x, &to));
+        Listing{
+            value: convert(self.value),
+            plus_error: convert(self.plus_error),
+            minus_error: convert(self.minus_error),
+            unit_text: unit_text.map(|unit_text| unit_text.to_string()),
+            ..self.clone()
+        }
+    }
+}
+
+// The listings of a summary value, i.e., "S035M" for the tau mass, with the value of each measurement. Its other
+// columns, i.e., "EVTS" or "CL%", are not the quantity, and a measurement without a "VALUE" column is left out
+pub fn find_listings(conn: &rusqlite::Connection, node_id: &str) -> Result<Vec<Listing>>{
+    let mut stmt = conn.prepare(
+        r#"
+        SELECT
+            pdgmeasurement.id AS measurement_id,
+            pdgreference.document_id,
+            pdgreference.publication_year,
+            pdgmeasurement_values.value,
+            pdgmeasurement_values.error_positive AS plus_error,
+            pdgmeasurement_values.error_negative AS minus_error,
+            pdgmeasurement_values.limit_type,
+            pdgmeasurement.confidence_level,
+            pdgmeasurement_values.unit_text,
+            pdgmeasurement.technique,
+            pdgmeasurement.comment,
+            pdgmeasurement_values.used_in_average
+        FROM
+            pdgmeasurement
+        INNER JOIN
+            pdgmeasurement_values
+        ON
+            pdgmeasurement_values.pdgmeasurement_id = pdgmeasurement.id
+        LEFT JOIN
+            pdgreference
+        ON
+            pdgreference.id = pdgmeasurement.pdgreference_id
+        WHERE
+            pdgmeasurement.pdgid = ?1
+        AND
+            (upper(pdgmeasurement_values.column_name) = 'VALUE' OR upper(pdgmeasurement_values.column_name) LIKE 'VALUE (%')
+        ORDER BY
+            pdgmeasurement.id, pdgmeasurement_values.id
+        "#,
+    )?;
+    let rows = stmt.query_map([node_id], |row| {
+        Ok(Listing{
+            measurement_id: row.get("measurement_id")?,
+            document_id: row.get("document_id")?,
+            year: row.get("publication_year")?,
+            value: row.get("value")?,
+            plus_error: row.get("plus_error")?,
+            minus_error: row.get("minus_error")?,
+            limit_type: row.get("limit_type")?,
+            confidence_level: row.get("confidence_level")?,
+            unit_text: row.get("unit_text")?,
+            technique: row.get("technique")?,
+            comment: row.get("comment")?,
+            used_in_average: row.get::<_, Option<bool>>("used_in_average")?.unwrap_or(false),
+        })
+    })?.collect::<Result<Vec<Listing>>>()?;
+    let mut listings: Vec<Listing> = Vec::new();
+    for listing in rows {
+        if listings.last().is_none_or(|last| last.measurement_id != listing.measurement_id) {
+            listings.push(listing);
+        }
+    }
+    Ok(listings)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WeightedAverage{
+    pub average: Measured, // the error includes the scale factor
+    pub chi2: f64,
+    pub count: usize,
+    pub scale_factor: f64, // at least one
+}
+
+impl WeightedAverage{
+    // Average of the central values used in the average, with their errors symmetrised, None without any
+    pub fn of(listings: &[Listing]) -> Option<WeightedAverage>{
+        let measurements = listings
+            .iter()
+            .filter(|listing| listing.used_in_average)
+            .filter_map(|listing| listing.measured())
+            .filter(|measured| measured.kind == Kind::Central)
+            .filter_map(|measured| Some((measured.value, measured.error().filter(|error| *error > 0.0)?.powi(-2))))
+            .collect::<Vec<(f64, f64)>>();
+        if measurements.is_empty() {
+            return None;
+        }
+        let total_weight = measurements.iter().map(|(_, weight)| weight).sum::<f64>();
+        let value = measurements.iter().map(|(x, weight)| weight * x).sum::<f64>() / total_weight;
+        let chi2 = measurements.iter().map(|(x, weight)| weight * (x - value).powi(2)).sum::<f64>();
+        let count = measurements.len();
+        let scale_factor = if count > 1 { (chi2 / (count - 1) as f64).sqrt().max(1.0) } else { 1.0 };
+        let error = scale_factor / total_weight.sqrt();
+        Some(WeightedAverage{ average: Measured::new(value, error, error), chi2, count, scale_factor })
+    }
+
+    // Difference to the quoted average in units of the quoted error, None for quoted limits or exact values
+    pub fn tension(&self, quoted: &Measured) -> Option<f64>{
+        if quoted.kind != Kind::Central {
+            return None;
+        }
+        let error = quoted.error().filter(|error| *error > 0.0)?;
+        Some((self.average.value - quoted.value) / error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn listing(measurement_id: i64, value: f64, error: f64, used_in_average: bool) -> Listing{
+        Listing{
+            measurement_id,
+            document_id: Some(format!("AUTHOR {}", 2000 + measurement_id)),
+            year: Some(2000 + measurement_id),
+            value: Some(value),
+            plus_error: Some(error),
+            minus_error: Some(error),
+            limit_type: None,
+            confidence_level: None,
+            unit_text: Some("MeV".to_string()),
+            technique: Some("BES3".to_string()),
+            comment: None,
+            used_in_average,
+        }
+    }
+
+    #[test]
+    fn test_weighted_average(){
+        let listings = [listing(1, 10.0, 1.0, true), listing(2, 12.0, 1.0, true), listing(3, 50.0, 0.1, false)];
+        let average = WeightedAverage::of(&listings).unwrap();
+        assert_eq!(average.count, 2);
+        assert!((average.average.value - 11.0).abs() < 1e-12);
+        // χ² = 2 for one degree of freedom, so S = sqrt(2)
+        assert!((average.chi2 - 2.0).abs() < 1e-12);
+        assert!((average.scale_factor - 2f64.sqrt()).abs() < 1e-12);
+        assert!((average.average.plus_error.unwrap() - 1.0).abs() < 1e-12);
+        assert!((average.tension(&Measured::new(11.5, 1.0, 1.0)).unwrap() + 0.5).abs() < 1e-12);
+        assert_eq!(average.tension(&Measured::upper_limit(11.0, None)), None);
+        assert_eq!(WeightedAverage::of(&listings[2..]), None);
+    }
+
+    #[test]
+    fn test_in_unit(){
+        let in_gev = listing(1, 1776.86, 0.12, true).in_unit(Some("GeV"));
+        assert!((in_gev.value.unwrap() - 1.77686).abs() < 1e-12);
+        assert!((in_gev.plus_error.unwrap() - 0.00012).abs() < 1e-12);
+        assert_eq!(in_gev.unit_text.as_deref(), Some("GeV"));
+        assert_eq!(listing(1, 1.0, 0.1, true).in_unit(Some("s")).unit_text.as_deref(), Some("MeV"));
+    }
+
+    #[test]
+    fn test_find_listings(){
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE pdgreference (id INTEGER, document_id TEXT, publication_name TEXT, publication_year INTEGER, doi TEXT, inspire_id TEXT, title TEXT);
+             CREATE TABLE pdgmeasurement (id INTEGER, pdgid TEXT, pdgreference_id INTEGER, event_count TEXT, confidence_level REAL, technique TEXT, charge TEXT, comment TEXT);
+             CREATE TABLE pdgmeasurement_values (id INTEGER, pdgmeasurement_id INTEGER, column_name TEXT, unit_text TEXT, value REAL, error_positive REAL, error_negative REAL, limit_type TEXT, used_in_average INTEGER);
+             INSERT INTO pdgreference VALUES (1, 'ABLIKIM 2014D', 'PR D90 012001', 2014, '10.1103/PhysRevD.90.012001', '1286574', NULL);
+             INSERT INTO pdgmeasurement VALUES (1, 'S035M', 1, '1171', NULL, 'BES3', NULL, 'Threshold scan');
+             INSERT INTO pdgmeasurement VALUES (2, 'S035M', NULL, NULL, 0.9, 'OPAL', NULL, NULL);
+             INSERT INTO pdgmeasurement VALUES (3, 'S035W', NULL, NULL, NULL, NULL, NULL, NULL);
+             INSERT INTO pdgmeasurement VALUES (4, 'S035M', NULL, '52', 0.9, 'ALEP', NULL, NULL);
+             INSERT INTO pdgmeasurement_values VALUES (1, 1, 'EVTS', NULL, 1171, NULL, NULL, NULL, 0);
+             INSERT INTO pdgmeasurement_values VALUES (2, 1, 'VALUE (MeV)', 'MeV', 1776.91, 0.12, 0.13, NULL, 1);
+             INSERT INTO pdgmeasurement_values VALUES (3, 2, 'VALUE', 'MeV', 1800, NULL, NULL, 'U', NULL);
+             INSERT INTO pdgmeasurement_values VALUES (4, 2, 'CL%', NULL, 90, NULL, NULL, NULL, NULL);
+             INSERT INTO pdgmeasurement_values VALUES (5, 4, 'EVTS', NULL, 52, NULL, NULL, NULL, 0);",
+        ).unwrap();
+        // the events before the value and the measurement with events only are not plotted
+        let listings = find_listings(&conn, "S035M").unwrap();
+        assert_eq!(listings.len(), 2);
+        assert_eq!(listings[0].document_id.as_deref(), Some("ABLIKIM 2014D"));
+        assert_eq!(listings[0].year, Some(2014));
+        assert_eq!(listings[0].value, Some(1776.91));
+        assert!(listings[0].used_in_average);
+        assert_eq!(listings[1].document_id, None);
+        assert_eq!(listings[1].measured(), Some(Measured::upper_limit(1800.0, Some(0.9))));
+        assert!(!listings[1].used_in_average);
+    }
+}
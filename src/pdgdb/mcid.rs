@@ -0,0 +1,335 @@
+// Decoder for the PDG Monte Carlo particle numbering scheme.
+// A code is read as the digits ±n n_r n_L n_q1 n_q2 n_q3 n_J, nuclei use ±10LZZZAAAI.
+// See https://pdg.lbl.gov/2024/reviews/rpp2024-rev-monte-carlo-numbering.pdf
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct McId(pub i64);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum McKind{
+    Quark,
+    Lepton,
+    GaugeBoson,
+    Higgs,
+    Diquark,
+    Meson,
+    Baryon,
+    Nucleus,
+    Other, // SUSY, technicolor, generator specific codes etc
+}
+
+impl std::fmt::Display for McKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let kind = match self {
+            McKind::Quark => "Quark",
+            McKind::Lepton => "Lepton",
+            McKind::GaugeBoson => "Gauge boson",
+            McKind::Higgs => "Higgs boson",
+            McKind::Diquark => "Diquark",
+            McKind::Meson => "Meson",
+            McKind::Baryon => "Baryon",
+            McKind::Nucleus => "Nucleus",
+            McKind::Other => "Other",
+        };
+        write!(f, "{}", kind)
+    }
+}
+
+const QUARK_NAMES: [&str; 8] = ["d", "u", "s", "c", "b", "t", "b'", "t'"];
+
+impl McId{
+    // position 1 is n_J, position 2 is n_q3 and so on
+    fn digit(&self, position: u32) -> i64{
+        (self.0.abs() / 10_i64.pow(position - 1)) % 10
+    }
+
+    pub fn n(&self) -> i64{ self.digit(7) }
+    pub fn n_r(&self) -> i64{ self.digit(6) }
+    pub fn n_l(&self) -> i64{ self.digit(5) }
+    pub fn n_q1(&self) -> i64{ self.digit(4) }
+    pub fn n_q2(&self) -> i64{ self.digit(3) }
+    pub fn n_q3(&self) -> i64{ self.digit(2) }
+    pub fn n_j(&self) -> i64{ self.digit(1) }
+
+    pub fn kind(&self) -> McKind{
+        let code = self.0.abs();
+        if self.is_nucleus(){
+            McKind::Nucleus
+        }
+        else if (1..=8).contains(&code){
+            McKind::Quark
+        }
+        else if self.is_lepton(){
+            McKind::Lepton
+        }
+        else if (21..=24).contains(&code) || code == 32 || code == 33 || code == 34{
+            McKind::GaugeBoson
+        }
+        else if code == 25 || (35..=37).contains(&code){
+            McKind::Higgs
+        }
+        else if matches!(self.n(), 1..=4 | 7 | 8){
+            // SUSY, excited fermions, technicolor and fourth generation hadrons
+            McKind::Other
+        }
+        else if self.is_baryon(){
+            McKind::Baryon
+        }
+        else if self.is_meson(){
+            McKind::Meson
+        }
+        else if self.n_q1() != 0 && self.n_q2() != 0 && self.n_q3() == 0{
+            McKind::Diquark
+        }
+        else{
+            McKind::Other
+        }
+    }
+
+    pub fn is_nucleus(&self) -> bool{
+        self.0.abs() >= 1_000_000_000
+    }
+
+    pub fn is_lepton(&self) -> bool{
+        (11..=18).contains(&self.0.abs())
+    }
+
+    pub fn is_meson(&self) -> bool{
+        !self.is_nucleus() && self.0.abs() > 100 && self.n_q1() == 0 && self.n_q2() != 0 && self.n_q3() != 0
+    }
+
+    pub fn is_baryon(&self) -> bool{
+        !self.is_nucleus() && self.n_q1() != 0 && self.n_q2() != 0 && self.n_q3() != 0
+    }
+
+    // K_S0 and K_L0 are mixtures of K0 and K0bar, so their strangeness is not defined
+    pub fn is_flavour_eigenstate(&self) -> bool{
+        !matches!(self.0.abs(), 130 | 310)
+    }
+
+    // 2J+1, the hadron codes carry it in n_J while the fundamental particles have a fixed spin
+    pub fn spin_multiplicity(&self) -> Option<i64>{
+        match self.kind() {
+            McKind::Quark | McKind::Lepton => Some(2),
+            McKind::GaugeBoson => Some(3),
+            McKind::Higgs => Some(1),
+            // K_L0 and K_S0 use n_J = 0 as a special marker, they are pseudoscalars
+            McKind::Meson if self.n_j() == 0 => Some(1),
+            McKind::Meson | McKind::Baryon | McKind::Diquark if self.n_j() > 0 => Some(self.n_j()),
+            _ => None,
+        }
+    }
+
+    // The total spin J as written in pdgparticle.quantum_j, i.e., "1/2" or "1"
+    pub fn spin_j(&self) -> Option<String>{
+        let multiplicity = self.spin_multiplicity()?;
+        let twice_j = multiplicity - 1;
+        if twice_j % 2 == 0 {
+            Some((twice_j / 2).to_string())
+        } else {
+            Some(format!("{}/2", twice_j))
+        }
+    }
+
+    // Compare the decoded J with the quantum_j column, None when either side cannot be interpreted
+    pub fn matches_quantum_j(&self, quantum_j: &str) -> Option<bool>{
+        let decoded = self.spin_multiplicity()?;
+        let quantum_j = quantum_j.trim().trim_matches(|c| c == '(' || c == ')');
+        let twice_j = match quantum_j.split_once('/') {
+            Some((numerator, "2")) => numerator.trim().parse::<i64>().ok()?,
+            Some(_) => return None,
+            None => quantum_j.parse::<i64>().ok()? * 2,
+        };
+        Some(twice_j + 1 == decoded)
+    }
+
+    pub fn is_self_conjugate(&self) -> bool{
+        match self.kind() {
+            McKind::GaugeBoson => matches!(self.0.abs(), 21 | 22 | 23 | 32 | 33),
+            McKind::Higgs => self.0.abs() != 37,
+            McKind::Meson => self.n_q2() == self.n_q3() || matches!(self.0.abs(), 130 | 310),
+            _ => false,
+        }
+    }
+
+    pub fn antiparticle(&self) -> McId{
+        if self.is_self_conjugate() { *self } else { McId(-self.0) }
+    }
+
+    // Valence quarks as signed quark codes, negative for antiquarks, i.e., K+ (321) gives [-3, 2]
+    pub fn quark_content(&self) -> Vec<i64>{
+        let sign = self.0.signum();
+        let quarks = if self.is_baryon(){
+            vec![self.n_q1(), self.n_q2(), self.n_q3()]
+        }
+        else if self.is_meson(){
+            // The heavier quark sits in n_q2, it is a quark for up-type flavours and an antiquark for down-type ones
+            let (heavy, light) = (self.n_q2(), self.n_q3());
+            if heavy % 2 == 0 { vec![heavy, -light] } else { vec![-heavy, light] }
+        }
+        else if (1..=8).contains(&self.0.abs()){
+            vec![self.0.abs()]
+        }
+        else{
+            Vec::new()
+        };
+        quarks.into_iter().map(|q| q * sign).collect()
+    }
+
+    // Human readable quark content with the quarks before the antiquarks, i.e., "u sbar" for the K+
+    pub fn quark_content_string(&self) -> Option<String>{
+        if !self.is_flavour_eigenstate() {
+            return Some("d sbar, dbar s mixture".to_string());
+        }
+        let mut quarks = self.quark_content();
+        quarks.sort_by_key(|q| *q < 0);
+        if quarks.is_empty() {
+            return None;
+        }
+        // digits 9 and 0 of the specials, i.e., the pomeron 990, are not quarks
+        let names = quarks
+            .iter()
+            .map(|q| {
+                let name = QUARK_NAMES.get((q.unsigned_abs() as usize).checked_sub(1)?)?;
+                Some(if *q < 0 { format!("{}bar", name) } else { name.to_string() })
+            })
+            .collect::<Option<Vec<String>>>()?;
+        Some(names.join(" "))
+    }
+
+    pub fn baryon_number(&self) -> i32{
+        if self.is_nucleus(){
+            let mass_number = (self.0.abs() / 10) % 1000;
+            return (self.0.signum() * mass_number) as i32;
+        }
+        let net_quarks: i64 = self.quark_content().iter().map(|q| q.signum()).sum();
+        // a lone quark carries 1/3, which cannot be represented, hadrons always sum to a multiple of 3
+        (net_quarks / 3) as i32
+    }
+
+    // Lepton numbers in the order (L_e, L_mu, L_tau)
+    pub fn lepton_numbers(&self) -> [i32; 3]{
+        let mut numbers = [0; 3];
+        if self.is_lepton(){
+            let generation = ((self.0.abs() - 11) / 2) as usize;
+            if generation < 3 {
+                numbers[generation] = self.0.signum() as i32;
+            }
+        }
+        numbers
+    }
+
+    // Net number of quarks minus antiquarks of the given flavour
+    pub fn net_flavour(&self, flavour: i64) -> i32{
+        if self.is_nucleus(){
+            // the number of strange quarks is stored as the number of Lambdas, L in 10LZZZAAAI
+            let lambdas = (self.0.abs() / 10_000_000) % 10;
+            return if flavour == 3 { (self.0.signum() * lambdas) as i32 } else { 0 };
+        }
+        self.quark_content()
+            .iter()
+            .filter(|q| q.abs() == flavour)
+            .map(|q| q.signum() as i32)
+            .sum()
+    }
+
+    // By convention strangeness and beauty are negative for s and b quarks
+    pub fn strangeness(&self) -> Option<i32>{
+        self.is_flavour_eigenstate().then(|| -self.net_flavour(3))
+    }
+
+    pub fn charm(&self) -> Option<i32>{
+        Some(self.net_flavour(4))
+    }
+
+    pub fn beauty(&self) -> Option<i32>{
+        Some(-self.net_flavour(5))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_meson_quark_content(){
+        assert_eq!(McId(211).quark_content(), vec![2, -1]);
+        assert_eq!(McId(-211).quark_content(), vec![-2, 1]);
+        assert_eq!(McId(321).quark_content(), vec![-3, 2]);
+        assert_eq!(McId(421).quark_content(), vec![4, -2]);
+        assert_eq!(McId(531).quark_content(), vec![-5, 3]);
+        assert_eq!(McId(443).quark_content(), vec![4, -4]);
+    }
+
+    #[test]
+    fn test_flavour_numbers(){
+        assert_eq!(McId(321).strangeness(), Some(1));
+        assert_eq!(McId(3122).strangeness(), Some(-1));
+        assert_eq!(McId(310).strangeness(), None);
+        assert_eq!(McId(411).charm(), Some(1));
+        assert_eq!(McId(521).beauty(), Some(1));
+        assert_eq!(McId(5122).beauty(), Some(-1));
+        assert_eq!(McId(-5122).beauty(), Some(1));
+    }
+
+    #[test]
+    fn test_digits_and_kind(){
+        let f0 = McId(9010221);
+        assert_eq!((f0.n(), f0.n_r(), f0.n_l(), f0.n_q1(), f0.n_q2(), f0.n_q3(), f0.n_j()), (9, 0, 1, 0, 2, 2, 1));
+        assert_eq!(f0.kind(), McKind::Meson);
+        assert_eq!(McId(2212).kind(), McKind::Baryon);
+        assert_eq!(McId(-13).kind(), McKind::Lepton);
+        assert_eq!(McId(23).kind(), McKind::GaugeBoson);
+        assert_eq!(McId(25).kind(), McKind::Higgs);
+        assert_eq!(McId(2101).kind(), McKind::Diquark);
+        assert_eq!(McId(130).kind(), McKind::Meson);
+        assert_eq!(McId(1000010020).kind(), McKind::Nucleus);
+        assert_eq!(McId(1000021).kind(), McKind::Other);
+    }
+
+    #[test]
+    fn test_spin(){
+        assert_eq!(McId(211).spin_j(), Some("0".to_string()));
+        assert_eq!(McId(213).spin_j(), Some("1".to_string()));
+        assert_eq!(McId(3334).spin_j(), Some("3/2".to_string()));
+        assert_eq!(McId(11).spin_j(), Some("1/2".to_string()));
+        assert_eq!(McId(117).matches_quantum_j("3"), Some(true));
+        assert_eq!(McId(2212).matches_quantum_j("1/2"), Some(true));
+        assert_eq!(McId(2212).matches_quantum_j("3/2"), Some(false));
+        assert_eq!(McId(2212).matches_quantum_j("?"), None);
+    }
+
+    #[test]
+    fn test_antiparticle(){
+        assert_eq!(McId(211).antiparticle(), McId(-211));
+        assert_eq!(McId(111).antiparticle(), McId(111));
+        assert_eq!(McId(22).antiparticle(), McId(22));
+        assert_eq!(McId(24).antiparticle(), McId(-24));
+        assert_eq!(McId(310).antiparticle(), McId(310));
+        assert_eq!(McId(311).antiparticle(), McId(-311));
+    }
+
+    #[test]
+    fn test_quark_content_string(){
+        assert_eq!(McId(321).quark_content_string(), Some("u sbar".to_string()));
+        assert_eq!(McId(-411).quark_content_string(), Some("d cbar".to_string()));
+        assert_eq!(McId(2212).quark_content_string(), Some("u u d".to_string()));
+        assert_eq!(McId(-2212).quark_content_string(), Some("ubar ubar dbar".to_string()));
+        assert_eq!(McId(11).quark_content_string(), None);
+        assert_eq!(McId(310).quark_content_string(), Some("d sbar, dbar s mixture".to_string()));
+        assert_eq!(McId(990).quark_content_string(), None);
+        assert_eq!(McId(9990).quark_content_string(), None);
+    }
+
+    #[test]
+    fn test_baryon_and_lepton_numbers(){
+        assert_eq!(McId(2212).baryon_number(), 1);
+        assert_eq!(McId(-2112).baryon_number(), -1);
+        assert_eq!(McId(211).baryon_number(), 0);
+        assert_eq!(McId(1000020040).baryon_number(), 4);
+        assert_eq!(McId(11).lepton_numbers(), [1, 0, 0]);
+        assert_eq!(McId(-14).lepton_numbers(), [0, -1, 0]);
+        assert_eq!(McId(15).lepton_numbers(), [0, 0, 1]);
+        assert_eq!(McId(22).lepton_numbers(), [0, 0, 0]);
+    }
+}
@@ -0,0 +1,306 @@
+// A PDG value with its asymmetric errors, or a limit, or a range, and arithmetic that propagates the errors.
+// Errors are combined linearly and in quadrature on each side, as for uncorrelated inputs, and an unknown error
+// leaves the combined error unknown. The values are taken as positive, like branching fractions, widths and lifetimes.
+use super::{ParticleDecay, ParticleMeasurement};
+use super::pdgDoc::LimitType;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Kind{
+    Central,
+    UpperLimit{ confidence_level: Option<f64> },
+    LowerLimit{ confidence_level: Option<f64> },
+    Range, // from value - minus_error to value + plus_error
+}
+
+impl Kind{
+    fn is_limit(&self) -> bool{
+        matches!(self, Kind::UpperLimit{ .. } | Kind::LowerLimit{ .. })
+    }
+
+    // Dividing by a limit or subtracting it turns an upper limit into a lower one
+    fn flipped(self) -> Kind{
+        match self {
+            Kind::UpperLimit{ confidence_level } => Kind::LowerLimit{ confidence_level },
+            Kind::LowerLimit{ confidence_level } => Kind::UpperLimit{ confidence_level },
+            kind => kind,
+        }
+    }
+
+    // The kind of a result, a value with a limit gives the limit, opposite limits give nothing
+    fn combine(self, other: Kind) -> Option<Kind>{
+        match (self, other) {
+            (Kind::Central, kind) | (kind, Kind::Central) => Some(kind),
+            (Kind::UpperLimit{ .. }, Kind::UpperLimit{ .. }) | (Kind::LowerLimit{ .. }, Kind::LowerLimit{ .. }) => Some(self),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Measured{
+    pub value: f64,
+    pub plus_error: Option<f64>, // None when unknown, zero when exact, always None for limits
+    pub minus_error: Option<f64>,
+    pub kind: Kind,
+}
+
+impl Measured{
+    pub fn new(value: f64, plus_error: f64, minus_error: f64) -> Measured{
+        Measured::central(value, Some(plus_error), Some(minus_error))
+    }
+
+    // A central value whose errors may be unknown
+    pub fn central(value: f64, plus_error: Option<f64>, minus_error: Option<f64>) -> Measured{
+        Measured{ value, plus_error, minus_error, kind: Kind::Central }
+    }
+
+    pub fn exact(value: f64) -> Measured{
+        Measured::new(value, 0.0, 0.0)
+    }
+
+    pub fn upper_limit(value: f64, confidence_level: Option<f64>) -> Measured{
+        Measured{ value, plus_error: None, minus_error: None, kind: Kind::UpperLimit{ confidence_level } }
+    }
+
+    pub fn lower_limit(value: f64, confidence_level: Option<f64>) -> Measured{
+        Measured{ value, plus_error: None, minus_error: None, kind: Kind::LowerLimit{ confidence_level } }
+    }
+
+    pub fn range(low: f64, high: f64) -> Measured{
+        let value = (low + high) / 2.0;
+        Measured{ value, plus_error: Some(high - value), minus_error: Some(value - low), kind: Kind::Range }
+    }
+
+    // Result of arithmetic, a limit keeps no errors
+    fn combined(value: f64, plus_error: Option<f64>, minus_error: Option<f64>, kind: Kind) -> Measured{
+        if kind.is_limit() {
+            Measured{ value, plus_error: None, minus_error: None, kind }
+        } else {
+            Measured{ value, plus_error, minus_error, kind }
+        }
+    }
+
+    // pdgdata columns, a range is given as value and errors. None without a value
+    pub fn from_pdgdata(value: Option<f64>, plus_error: Option<f64>, minus_error: Option<f64>, limit_type: Option<&str>, confidence_level: Option<f64>) -> Option<Measured>{
+        let value = value.filter(|value| value.is_finite())?;
+        let error = |error: Option<f64>| error.filter(|error| error.is_finite()).map(f64::abs);
+        Some(match limit_type.and_then(LimitType::decode) {
+            Some(LimitType::Upper) => Measured::upper_limit(value, confidence_level),
+            Some(LimitType::Lower) => Measured::lower_limit(value, confidence_level),
+            Some(LimitType::Range) => Measured::range(value - error(minus_error).unwrap_or_default(), value + error(plus_error).unwrap_or_default()),
+            None => Measured::central(value, error(plus_error), error(minus_error)),
+        })
+    }
+
+    pub fn from_decay(decay: &ParticleDecay) -> Option<Measured>{
+        Measured::from_pdgdata(decay.value, decay.plus_error, decay.minus_error, decay.limit_type.as_deref(), decay.confidence_level)
+    }
+
+    pub fn from_measurement(measurement: &ParticleMeasurement) -> Option<Measured>{
+        Measured::from_pdgdata(
+            measurement.value,
+            measurement.plus_error,
+            measurement.minus_error,
+            measurement.limit_type.as_deref(),
+            measurement.confidence_level,
+        )
+    }
+
+    pub fn is_limit(&self) -> bool{
+        self.kind.is_limit()
+    }
+
+    pub fn has_errors(&self) -> bool{
+        self.plus_error.into_iter().chain(self.minus_error).any(|error| error > 0.0)
+    }
+
+    // Ends of the interval, an unknown error gives no extent on its side
+    pub fn low(&self) -> f64{
+        self.value - self.minus_error.unwrap_or_default()
+    }
+
+    pub fn high(&self) -> f64{
+        self.value + self.plus_error.unwrap_or_default()
+    }
+
+    // Mean of both errors, for symmetric approximations, None when either is unknown
+    pub fn error(&self) -> Option<f64>{
+        Some((self.plus_error? + self.minus_error?) / 2.0)
+    }
+
+    // Distance of x from the value in units of the error on its side, None for limits, ranges and missing errors
+    pub fn pull(&self, x: f64) -> Option<f64>{
+        if self.kind != Kind::Central {
+            return None;
+        }
+        let error = if x > self.value { self.plus_error } else { self.minus_error };
+        error.filter(|error| *error > 0.0).map(|error| (x - self.value) / error)
+    }
+
+    // Exact factor, a negative one swaps the errors and the limit
+    pub fn scaled(&self, factor: f64) -> Measured{
+        let (plus_error, minus_error, kind) = if factor < 0.0 {
+            (self.minus_error, self.plus_error, self.kind.flipped())
+        } else {
+            (self.plus_error, self.minus_error, self.kind)
+        };
+        let scale = |error: Option<f64>| error.map(|error| error * factor.abs());
+        Measured{ value: self.value * factor, plus_error: scale(plus_error), minus_error: scale(minus_error), kind }
+    }
+
+    // Ranges only combine with ranges, by their ends
+    fn range_with(&self, other: &Measured, operation: fn(f64, f64) -> f64, flips: bool) -> Option<Measured>{
+        if self.kind != Kind::Range || other.kind != Kind::Range {
+            return None;
+        }
+        let (other_low, other_high) = if flips { (other.high(), other.low()) } else { (other.low(), other.high()) };
+        let ends = [operation(self.low(), other_low), operation(self.high(), other_high)];
+        Some(Measured::range(ends[0].min(ends[1]), ends[0].max(ends[1])))
+    }
+
+    pub fn sum(&self, other: &Measured) -> Option<Measured>{
+        if self.kind == Kind::Range || other.kind == Kind::Range {
+            return self.range_with(other, |a, b| a + b, false);
+        }
+        Some(Measured::combined(
+            self.value + other.value,
+            quadrature(self.plus_error, other.plus_error),
+            quadrature(self.minus_error, other.minus_error),
+            self.kind.combine(other.kind)?,
+        ))
+    }
+
+    pub fn difference(&self, other: &Measured) -> Option<Measured>{
+        if self.kind == Kind::Range || other.kind == Kind::Range {
+            return self.range_with(other, |a, b| a - b, true);
+        }
+        Some(Measured::combined(
+            self.value - other.value,
+            quadrature(self.plus_error, other.minus_error),
+            quadrature(self.minus_error, other.plus_error),
+            self.kind.combine(other.kind.flipped())?,
+        ))
+    }
+
+    // Relative errors add in quadrature
+    pub fn product(&self, other: &Measured) -> Option<Measured>{
+        if self.kind == Kind::Range || other.kind == Kind::Range {
+            return self.range_with(other, |a, b| a * b, false);
+        }
+        let value = self.value * other.value;
+        let error = |error: Option<f64>, other_error: Option<f64>| {
+            quadrature(relative(error, self.value), relative(other_error, other.value)).map(|relative| value.abs() * relative)
+        };
+        Some(Measured::combined(
+            value,
+            error(self.plus_error, other.plus_error),
+            error(self.minus_error, other.minus_error),
+            self.kind.combine(other.kind)?,
+        ))
+    }
+
+    // A larger denominator gives a smaller ratio, so its errors and limits swap sides
+    pub fn ratio(&self, other: &Measured) -> Option<Measured>{
+        if self.kind == Kind::Range || other.kind == Kind::Range {
+            return self.range_with(other, |a, b| a / b, true);
+        }
+        if other.value == 0.0 {
+            return None;
+        }
+        let value = self.value / other.value;
+        let error = |error: Option<f64>, other_error: Option<f64>| {
+            quadrature(relative(error, self.value), relative(other_error, other.value)).map(|relative| value.abs() * relative)
+        };
+        Some(Measured::combined(
+            value,
+            error(self.plus_error, other.minus_error),
+            error(self.minus_error, other.plus_error),
+            self.kind.combine(other.kind.flipped())?,
+        ))
+    }
+}
+
+fn quadrature(a: Option<f64>, b: Option<f64>) -> Option<f64>{
+    Some(a?.hypot(b?))
+}
+
+fn relative(error: Option<f64>, value: f64) -> Option<f64>{
+    if value == 0.0 { error.map(|_| 0.0) } else { error.map(|error| error / value.abs()) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: f64, b: f64) -> bool{
+        (a - b).abs() <= 1e-12 * b.abs().max(1.0)
+    }
+
+    #[test]
+    fn test_from_pdgdata(){
+        let limit = Measured::from_pdgdata(Some(4.2e-13), None, None, Some("U"), Some(0.9)).unwrap();
+        assert_eq!(limit.kind, Kind::UpperLimit{ confidence_level: Some(0.9) });
+        let range = Measured::from_pdgdata(Some(1.0), Some(0.5), Some(0.25), Some("R"), None).unwrap();
+        assert_eq!((range.low(), range.high()), (0.75, 1.5));
+        let central = Measured::from_pdgdata(Some(0.0395), Some(0.0003), None, None, None).unwrap();
+        assert_eq!(central, Measured::central(0.0395, Some(0.0003), None));
+        assert_eq!(Measured::from_pdgdata(None, Some(0.1), Some(0.1), None, None), None);
+        assert_eq!(Measured::from_pdgdata(Some(f64::NAN), None, None, None, None), None);
+    }
+
+    #[test]
+    fn test_product_and_ratio(){
+        // B(D*+ -> D0 pi+) B(D0 -> K- pi+)
+        let product = Measured::new(0.677, 0.005, 0.005).product(&Measured::new(0.0395, 0.0003, 0.0002)).unwrap();
+        assert!(close(product.value, 0.677 * 0.0395));
+        let plus = product.value * ((0.005_f64 / 0.677).powi(2) + (0.0003_f64 / 0.0395).powi(2)).sqrt();
+        assert!(close(product.plus_error.unwrap(), plus));
+        assert!(product.minus_error < product.plus_error);
+        // a width from the lifetime, a longer lifetime is a smaller width
+        let width = Measured::exact(6.582119569e-25).ratio(&Measured::new(2.0e-6, 0.2e-6, 0.1e-6)).unwrap();
+        assert!(close(width.value, 6.582119569e-25 / 2.0e-6));
+        assert!(close(width.minus_error.unwrap() / width.value, 0.1));
+        assert!(close(width.plus_error.unwrap() / width.value, 0.05));
+        // an unknown error stays unknown instead of counting as exact
+        let ratio = Measured::central(0.04, None, Some(0.002)).ratio(&Measured::new(0.5, 0.1, 0.1)).unwrap();
+        assert_eq!(ratio.plus_error, None);
+        assert!(ratio.minus_error.is_some());
+        assert_eq!(Measured::central(0.04, None, None).pull(0.05), None);
+    }
+
+    #[test]
+    fn test_sum_and_difference(){
+        let sum = Measured::new(1.0, 0.3, 0.4).sum(&Measured::new(2.0, 0.4, 0.3)).unwrap();
+        assert!(close(sum.value, 3.0) && close(sum.plus_error.unwrap(), 0.5) && close(sum.minus_error.unwrap(), 0.5));
+        let difference = Measured::new(2.0, 0.3, 0.0).difference(&Measured::new(1.0, 0.4, 0.0)).unwrap();
+        assert!(close(difference.plus_error.unwrap(), 0.3) && close(difference.minus_error.unwrap(), 0.4));
+    }
+
+    #[test]
+    fn test_limits(){
+        let limit = Measured::upper_limit(1e-5, Some(0.9));
+        let scaled = limit.product(&Measured::new(0.5, 0.1, 0.1)).unwrap();
+        assert_eq!(scaled.kind, Kind::UpperLimit{ confidence_level: Some(0.9) });
+        assert!(close(scaled.value, 5e-6));
+        assert_eq!(scaled.plus_error, None);
+        // one over an upper limit is a lower limit
+        let inverse = Measured::exact(1.0).ratio(&limit).unwrap();
+        assert!(matches!(inverse.kind, Kind::LowerLimit{ .. }));
+        assert_eq!(limit.product(&Measured::lower_limit(2.0, None)), None);
+        assert_eq!(limit.pull(0.0), None);
+        assert!(matches!(limit.scaled(-1.0).kind, Kind::LowerLimit{ .. }));
+    }
+
+    #[test]
+    fn test_ranges_and_pull(){
+        let range = Measured::range(1.0, 2.0).product(&Measured::range(3.0, 4.0)).unwrap();
+        assert_eq!((range.low(), range.high()), (3.0, 8.0));
+        let ratio = Measured::range(1.0, 2.0).ratio(&Measured::range(4.0, 8.0)).unwrap();
+        assert_eq!((ratio.low(), ratio.high()), (0.125, 0.5));
+        assert_eq!(Measured::range(1.0, 2.0).sum(&Measured::exact(1.0)), None);
+        let central = Measured::new(0.04, 0.005, 0.002);
+        assert!(close(central.pull(0.05).unwrap(), 2.0));
+        assert!(close(central.pull(0.03).unwrap(), -5.0));
+        assert_eq!(Measured::new(0.04, 0.005, 0.0).pull(0.03), None);
+    }
+}
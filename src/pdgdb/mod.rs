@@ -1,8 +1,29 @@
-use std::{collections::HashMap, io::Error};
+use std::{collections::HashMap, io::Error, sync::Arc};
 use rusqlite::Result;
+use mcid::McId;
+use measured::Measured;
+use dataType::DataType;
+use pdgDoc::PdgDoc;
+use footnotes::{find_footnotes, footnote_ids, Footnote};
+
+const HBAR_GEV_S: f64 = 6.582119569e-25; // reduced Planck constant in GeV s
+const C_MM_PER_S: f64 = 2.99792458e11; // speed of light in mm/s
+// --only filters that keep the decay table
+const DECAY_FILTERS: [&str; 2] = ["decay", "decays"];
 
 pub mod connection;
 pub mod queries;
+pub mod mcid;
+pub mod conservation;
+pub mod finalStates;
+pub mod naming;
+pub mod quantity;
+pub mod measured;
+pub mod dataType;
+pub mod pdgDoc;
+pub mod listings;
+pub mod references;
+pub mod footnotes;
 
 
 #[derive(Debug, Default)]
@@ -26,6 +47,8 @@ pub struct Particle
 
     pub decay: Option<Vec<ParticleDecay>>,
     pub measurements: Option<Vec<ParticleMeasurement>>,
+    pub doc: Arc<PdgDoc>, // pdgdoc of the connection the particle was read from
+    pub footnotes: Vec<Footnote>, // footnotes of the decays and measurements, which keep their ids
 
     id: Option<u64>,
     pdgid_id: Option<u64>, // an internal id used to link the particle to the pdgid table
@@ -34,6 +57,16 @@ pub struct Particle
 }
 
 impl Particle{
+    pub fn mcid(&self) -> Option<McId>{
+        self.pdgid.map(McId)
+    }
+
+    // Whether the spin decoded from the mcid agrees with pdgparticle.quantum_j, None if either is missing
+    pub fn spin_consistent(&self) -> Option<bool>{
+        let mcid = self.mcid()?;
+        mcid.matches_quantum_j(self.j_spin.as_deref()?)
+    }
+
     pub fn find_decay(&mut self, conn: &rusqlite::Connection){
         let search_node_id =  self
             .node_id
@@ -48,11 +81,14 @@ impl Particle{
                 pdgid.sort,
                 pdgid.mode_number,
                 pdgid.description,
+                pdgid.data_type,
+                pdgid.flags,
                 pdgdata.display_value_text,
                 pdgdata.value,
                 pdgdata.error_positive AS plus_error,
                 pdgdata.error_negative AS minus_error,
-                pdgdata.limit_type
+                pdgdata.limit_type,
+                pdgdata.confidence_level
             FROM
                 pdgid
             INNER JOIN
@@ -70,14 +106,23 @@ impl Particle{
                 sort_order: row.get("sort")?,
                 mode_number: row.get("mode_number")?,
                 description: row.get("description")?,
+                data_type: row.get("data_type")?,
+                flags: row.get("flags")?,
                 display_value: row.get("display_value_text")?,
                 value: row.get("value")?,
                 plus_error: row.get("plus_error")?,
                 minus_error: row.get("minus_error")?,
                 limit_type: row.get("limit_type")?,
+                confidence_level: row.get("confidence_level")?,
+                footnote_ids: Vec::new(),
             })
         }).unwrap().collect::<Result<Vec<ParticleDecay>>>().unwrap();
         decay_data.sort_by_key(|decay| decay.mode_number );
+        let footnotes = find_footnotes(conn, &search_node_id);
+        for decay in decay_data.iter_mut() {
+            decay.footnote_ids = footnote_ids(&footnotes, decay.node_id.as_deref());
+        }
+        self.add_footnotes(footnotes);
         self.decay = Some(decay_data);
     }
 
@@ -100,12 +145,14 @@ impl Particle{
                 pdgid.pdgid,
                 pdgid.description,
                 pdgid.data_type,
+                pdgid.flags,
                 pdgdata.display_value_text,
                 pdgdata.value,
                 pdgdata.display_power_of_ten,
                 pdgdata.unit_text,
                 pdgdata.scale_factor,
                 pdgdata.limit_type,
+                pdgdata.confidence_level,
                 pdgdata.error_positive AS plus_error,
                 pdgdata.error_negative AS minus_error
             FROM
@@ -120,11 +167,13 @@ impl Particle{
                 pdgid.pdgid NOT LIKE ?2
             "#,
         ).unwrap();
-        let measurement_data = stmt.query_map(&[&search_node_id, &avoid_decay_node], |row|{
+        let mut measurement_data = stmt.query_map(&[&search_node_id, &avoid_decay_node], |row|{
+            let data_type: Option<String> = row.get("data_type")?;
             Ok(ParticleMeasurement{
                 node_id: row.get("pdgid")?,
                 description: row.get("description")?,
-                data_type: row.get("data_type")?,
+                kind: self.doc.data_type(data_type.as_deref().unwrap_or("")),
+                flags: row.get("flags")?,
 
                 value: row.get("value")?,
                 display_value: row.get("display_value_text")?,
@@ -132,41 +181,171 @@ impl Particle{
                 unit_text: row.get("unit_text")?,
                 scale_factor: row.get("scale_factor")?,
                 limit_type: row.get("limit_type")?,
+                confidence_level: row.get("confidence_level")?,
                 plus_error: row.get("plus_error")?,
                 minus_error: row.get("minus_error")?,
+                footnote_ids: Vec::new(),
             })
         }).unwrap().collect::<Result<Vec<ParticleMeasurement>>>().unwrap();
+        let footnotes = find_footnotes(conn, &search_node_id);
+        for measurement in measurement_data.iter_mut() {
+            measurement.footnote_ids = footnote_ids(&footnotes, measurement.node_id.as_deref());
+        }
+        self.add_footnotes(footnotes);
+        self.mass = Particle::summary_value(&measurement_data, &DataType::Mass);
+        self.decay_width = Particle::summary_value(&measurement_data, &DataType::Width);
         self.measurements = Some(measurement_data);
     }
+
+    // Footnotes of a decay or measurement, the same footnote is kept once
+    fn add_footnotes(&mut self, footnotes: Vec<Footnote>){
+        for footnote in footnotes {
+            if !self.footnotes.iter().any(|known| known.id == footnote.id) {
+                self.footnotes.push(footnote);
+            }
+        }
+    }
+
+    pub fn footnote(&self, id: i64) -> Option<&Footnote>{
+        self.footnotes.iter().find(|footnote| footnote.id == id)
+    }
+
+    // Mean decay length c*tau in mm, from the lifetime in s or otherwise from the width, None for stable particles
+    pub fn ctau(&self) -> Option<f64>{
+        let lifetime = self.measurements
+            .as_deref()
+            .and_then(|measurements| Particle::summary_value(measurements, &DataType::Lifetime))
+            .or_else(|| self.decay_width.filter(|width| *width > 0.0).map(|width| HBAR_GEV_S / width))?;
+        Some(lifetime * C_MM_PER_S)
+    }
+
+    // Total width in GeV, from the width or otherwise from the lifetime in s
+    pub fn total_width(&self) -> Option<f64>{
+        self.decay_width.or_else(|| self.measured_width().map(|width| width.value))
+    }
+
+    // Total width in GeV with its errors, from the width or otherwise as ħ over the lifetime.
+    // Limits and ranges are not a width, so a particle with only those has none
+    pub fn measured_width(&self) -> Option<Measured>{
+        let value = |kind: &DataType| self.summary_measurement(kind)
+            .filter(|measurement| measurement.limit_type.is_none())
+            .and_then(Measured::from_measurement);
+        if let Some(width) = value(&DataType::Width) {
+            return Some(width);
+        }
+        Measured::exact(HBAR_GEV_S).ratio(&value(&DataType::Lifetime)?)
+    }
+
+    // The first measurement of the given data type, i.e., the mass or the width
+    pub fn summary_measurement(&self, kind: &DataType) -> Option<&ParticleMeasurement>{
+        self.measurements
+            .iter()
+            .flatten()
+            .find(|measurement| measurement.kind == *kind)
+    }
+
+    // limit_type and flags codes of the decays and measurements with their pdgdoc meaning, sorted by code,
+    // undocumented codes are left out
+    pub fn code_legend(&self) -> Vec<(String, String)>{
+        let decays = self.decay.iter().flatten().map(|decay| (decay.limit_type.as_deref(), decay.flags.as_deref()));
+        let measurements = self.measurements.iter().flatten().map(|measurement| (measurement.limit_type.as_deref(), measurement.flags.as_deref()));
+        let mut legend: Vec<(String, String)> = Vec::new();
+        for (limit_type, flags) in decays.chain(measurements) {
+            let limit = limit_type.and_then(|code| Some((code.to_string(), self.doc.limit_type(code)?.to_string())));
+            for entry in limit.into_iter().chain(self.doc.flags(flags.unwrap_or(""))) {
+                if !legend.contains(&entry) {
+                    legend.push(entry);
+                }
+            }
+        }
+        legend.sort();
+        legend
+    }
+
+    // Keeps the measurements of the --only sections, the decays only when "decays" is one of them. A filter that
+    // names no data type and no section of the particle is an error listing the valid filters
+    pub fn retain_sections(&mut self, filters: &[String]) -> Result<(), String>{
+        if filters.is_empty() {
+            return Ok(());
+        }
+        let kinds = DataType::KNOWN
+            .iter()
+            .cloned()
+            .chain(self.measurements.iter().flatten().map(|measurement| measurement.kind.clone()))
+            .collect::<Vec<DataType>>();
+        let is_decay = |filter: &String| DECAY_FILTERS.contains(&filter.trim().to_lowercase().as_str());
+        if let Some(unknown) = filters.iter().find(|filter| !is_decay(filter) && !kinds.iter().any(|kind| kind.matches(filter))) {
+            let mut valid = kinds
+                .iter()
+                .flat_map(|kind| kind.aliases())
+                .chain(DECAY_FILTERS.iter().map(|filter| filter.to_string()))
+                .collect::<Vec<String>>();
+            valid.sort();
+            valid.dedup();
+            return Err(format!("Unknown section {} for --only, valid sections are: {}", unknown.trim(), valid.join(", ")));
+        }
+        if !filters.iter().any(is_decay) {
+            self.decay = None;
+        }
+        if let Some(measurements) = &mut self.measurements {
+            measurements.retain(|measurement| filters.iter().any(|filter| measurement.kind.matches(filter)));
+        }
+        Ok(())
+    }
+
+    // The first value of the given data type, masses and widths are in GeV
+    fn summary_value(measurements: &[ParticleMeasurement], kind: &DataType) -> Option<f64>{
+        measurements
+            .iter()
+            .find(|measurement| measurement.kind == *kind)
+            .and_then(|measurement| measurement.value)
+    }
 }
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct ParticleDecay
 {
     pub node_id: Option<String>, // S003M for electron mass etc
     pub sort_order: Option<i64>, //sort in pdgid
     pub mode_number: Option<i64>, //mode_number in pdgid
     pub description: Option<String>, // description in pdgid
+    pub data_type: Option<String>, // data_type in pdgid, BFI for inclusive modes
+    pub flags: Option<String>, // flags in pdgid
     pub display_value: Option<String>, // display_value in pdgdata
     pub value: Option<f64>, // value in pdgdata
     pub plus_error: Option<f64>, // error_positive in pdgdata,
     pub minus_error: Option<f64>, // error_negative in pdgdata
     pub limit_type: Option<String>, // limit_type in pdgdata
+    pub confidence_level: Option<f64>, // confidence_level in pdgdata, of limits
+    pub footnote_ids: Vec<i64>, // ids of the footnotes in pdgfootnote, in their order
 }
 
-#[derive(Debug)]
+impl ParticleDecay{
+    // Modes with a measured fraction that describe a complete final state, so they can be used as a decay channel
+    pub fn is_exclusive_measurement(&self) -> bool{
+        let is_limit = Measured::from_decay(self).is_some_and(|measured| measured.is_limit());
+        let is_inclusive = self.data_type.as_deref().is_some_and(|data_type| data_type.starts_with("BFI"))
+            || self.description.as_deref().is_some_and(|description| description.contains("anything"));
+        self.value.is_some() && !is_limit && !is_inclusive
+    }
+}
+
+#[derive(Debug, Clone, Default)]
 pub struct ParticleMeasurement
 {
     pub node_id: Option<String>, // pdgid in the databases, S003M for electron mass
     pub description: Option<String>, // description in pdgid
-    pub data_type: Option<String>, // data_type in pdgid
+    pub kind: DataType, // data_type in pdgid decoded with pdgdoc, its code() is the raw column
+    pub flags: Option<String>, // flags in pdgid
     pub value: Option<f64>, // value in pdgdata
     pub display_value: Option<String>, // display_value in pdgdata
     pub display_power_of_ten: Option<i64>, // display_order in pdgdata
     pub unit_text: Option<String>, // unit_text in pdgdata
     pub scale_factor: Option<f64>, // scale_factor in pdgdata
     pub limit_type: Option<String>, // limit_type in pdgdata
+    pub confidence_level: Option<f64>, // confidence_level in pdgdata, of limits
     pub plus_error: Option<f64>, // error_positive in pdgdata,
     pub minus_error: Option<f64>, // error_negative in pdgdata
+    pub footnote_ids: Vec<i64>, // ids of the footnotes in pdgfootnote, in their order
 }
 
 
@@ -195,11 +374,117 @@ impl DecayChannel{
 }
 
 
+#[cfg(test)]
+impl ParticleDecay{
+    // An exclusive branching fraction, the columns tests do not need are empty
+    pub fn test(node_id: &str, value: f64, limit_type: Option<&str>) -> Self{
+        ParticleDecay{
+            node_id: Some(node_id.to_string()),
+            data_type: Some("BFX".to_string()),
+            value: Some(value),
+            limit_type: limit_type.map(|limit_type| limit_type.to_string()),
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+impl ParticleMeasurement{
+    // A central value, its data type decoded without pdgdoc
+    pub fn test(node_id: &str, data_type: &str, value: f64, unit_text: &str) -> Self{
+        ParticleMeasurement{
+            node_id: Some(node_id.to_string()),
+            kind: PdgDoc::default().data_type(data_type),
+            value: Some(value),
+            unit_text: Some(unit_text.to_string()),
+            ..Default::default()
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::pdgdb::connection::connect;
 
+    #[test]
+    fn test_spin_consistent(){
+        let mut muon = Particle::test_muon();
+        assert_eq!(muon.spin_consistent(), Some(true));
+        muon.j_spin = Some("3/2".to_string());
+        assert_eq!(muon.spin_consistent(), Some(false));
+        muon.pdgid = None;
+        assert_eq!(muon.spin_consistent(), None);
+    }
+
+    #[test]
+    fn test_ctau_from_width(){
+        let mut muon = Particle::test_muon();
+        assert_eq!(muon.ctau(), None);
+        muon.decay_width = Some(2.99598e-19);
+        let ctau = muon.ctau().unwrap();
+        assert!((ctau - 658.6e3).abs() < 100.0, "c tau of the muon is 658.6 m, got {} mm", ctau);
+    }
+
+    #[test]
+    fn test_total_width_from_lifetime(){
+        let mut muon = Particle::test_muon();
+        assert_eq!(muon.total_width(), None);
+        muon.measurements = Some(vec![ParticleMeasurement::test("S004T", "T", 2.1969811e-6, "s")]);
+        let width = muon.total_width().unwrap();
+        assert!((width - 2.99598e-19).abs() < 1e-23);
+
+        // a lifetime limit gives no width
+        muon.measurements.as_mut().unwrap()[0].limit_type = Some("L".to_string());
+        assert_eq!(muon.measured_width(), None);
+        assert_eq!(muon.total_width(), None);
+    }
+
+    #[test]
+    fn test_code_legend(){
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE pdgdoc (id INTEGER, table_name TEXT, column_name TEXT, value TEXT, indicator TEXT, description TEXT, comment TEXT);
+             INSERT INTO pdgdoc VALUES (1, 'PDGDATA', 'LIMIT_TYPE', 'U', NULL, 'Upper limit', NULL);
+             INSERT INTO pdgdoc VALUES (2, 'PDGID', 'FLAGS', 'D', NULL, 'Data in a footnote', NULL);",
+        ).unwrap();
+        let mut muon = Particle::test_muon();
+        muon.doc = Arc::new(PdgDoc::load(&conn));
+        let decay = |limit_type: Option<&str>, flags: Option<&str>| ParticleDecay{
+            flags: flags.map(|flags| flags.to_string()),
+            ..ParticleDecay::test("S004.1", 1e-12, limit_type)
+        };
+        muon.decay = Some(vec![decay(Some("U"), None), decay(Some("U"), Some("DZ")), decay(Some("R"), None)]);
+        assert_eq!(muon.code_legend(), vec![
+            ("D".to_string(), "Data in a footnote".to_string()),
+            ("U".to_string(), "Upper limit".to_string()),
+        ]);
+    }
+
+    #[test]
+    fn test_retain_sections(){
+        let mut muon = Particle::test_muon();
+        let lifetime = ParticleMeasurement::test("S004T", "T", 2.1969811e-6, "s");
+        let moment = ParticleMeasurement{
+            kind: DataType::Other{ code: "MM".to_string(), description: Some("Magnetic moment".to_string()) },
+            ..ParticleMeasurement::test("S004MM", "MM", 1.00116592, "e/2m_mu")
+        };
+        muon.measurements = Some(vec![lifetime.clone(), moment]);
+        muon.decay = Some(Vec::new());
+        muon.retain_sections(&[]).unwrap();
+        assert_eq!(muon.measurements.as_ref().map(|measurements| measurements.len()), Some(2));
+        let error = muon.retain_sections(&["masse".to_string()]).unwrap_err();
+        assert!(error.starts_with("Unknown section masse for --only"), "{}", error);
+        assert!(error.contains("decays") && error.contains("masses") && error.contains("magnetic moment"), "{}", error);
+        assert_eq!(muon.measurements.as_ref().map(|measurements| measurements.len()), Some(2));
+        muon.retain_sections(&["magnetic".to_string(), "decays".to_string()]).unwrap();
+        assert_eq!(muon.measurements.as_ref().unwrap()[0].node_id.as_deref(), Some("S004MM"));
+        assert!(muon.decay.is_some());
+        muon.retain_sections(&["lifetime".to_string()]).unwrap();
+        assert!(muon.measurements.as_ref().unwrap().is_empty());
+        assert!(muon.decay.is_none());
+    }
+
     #[test]
     fn test_particle_decay(){
         let conn = connect().unwrap();
@@ -237,6 +522,8 @@ impl Particle{
             pdgid_id: Some(464),
             pdgitem_id: Some(76255),
             measurements: None,
+            doc: Arc::default(),
+            footnotes: Vec::new(),
         }
     }
 }
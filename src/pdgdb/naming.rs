@@ -0,0 +1,290 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::OnceLock;
+use dotenv::from_path;
+use crate::pdgdb::DecayChannel;
+
+static NAME_TABLE: OnceLock<NameTable> = OnceLock::new();
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Convention{
+    Pdg,
+    EvtGen,
+    Pythia,
+    Geant4,
+    Root,
+    Latex,
+}
+
+const CONVENTIONS: [Convention; 6] = [
+    Convention::Pdg,
+    Convention::EvtGen,
+    Convention::Pythia,
+    Convention::Geant4,
+    Convention::Root,
+    Convention::Latex,
+];
+
+impl fmt::Display for Convention{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result{
+        let name = match self {
+            Convention::Pdg => "PDG",
+            Convention::EvtGen => "EvtGen",
+            Convention::Pythia => "Pythia",
+            Convention::Geant4 => "Geant4",
+            Convention::Root => "ROOT",
+            Convention::Latex => "LaTeX",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for Convention{
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err>{
+        CONVENTIONS
+            .iter()
+            .find(|convention| convention.to_string().eq_ignore_ascii_case(s))
+            .copied()
+            .ok_or(format!("Unknown naming convention {}, use pdg, evtgen, pythia, geant4, root or latex", s))
+    }
+}
+
+// MC ID, then the name in every convention in the order of CONVENTIONS, "" where the convention has no name
+const BUILTIN_NAMES: [(i64, [&str; 6]); 71] = [
+    (11, ["e-", "e-", "e-", "e-", "e-", "e^{-}"]),
+    (-11, ["e+", "e+", "e+", "e+", "e+", "e^{+}"]),
+    (12, ["nu_e", "nu_e", "nu_e", "nu_e", "nu_e", "\\nu_{e}"]),
+    (-12, ["nubar_e", "anti-nu_e", "nu_ebar", "anti_nu_e", "nu_ebar", "\\bar{\\nu}_{e}"]),
+    (13, ["mu-", "mu-", "mu-", "mu-", "mu-", "\\mu^{-}"]),
+    (-13, ["mu+", "mu+", "mu+", "mu+", "mu+", "\\mu^{+}"]),
+    (14, ["nu_mu", "nu_mu", "nu_mu", "nu_mu", "nu_mu", "\\nu_{\\mu}"]),
+    (-14, ["nubar_mu", "anti-nu_mu", "nu_mubar", "anti_nu_mu", "nu_mubar", "\\bar{\\nu}_{\\mu}"]),
+    (15, ["tau-", "tau-", "tau-", "tau-", "tau-", "\\tau^{-}"]),
+    (-15, ["tau+", "tau+", "tau+", "tau+", "tau+", "\\tau^{+}"]),
+    (16, ["nu_tau", "nu_tau", "nu_tau", "nu_tau", "nu_tau", "\\nu_{\\tau}"]),
+    (-16, ["nubar_tau", "anti-nu_tau", "nu_taubar", "anti_nu_tau", "nu_taubar", "\\bar{\\nu}_{\\tau}"]),
+    (22, ["gamma", "gamma", "gamma", "gamma", "gamma", "\\gamma"]),
+    (23, ["Z", "Z0", "Z0", "Z0", "Z0", "Z^{0}"]),
+    (24, ["W+", "W+", "W+", "W+", "W+", "W^{+}"]),
+    (-24, ["W-", "W-", "W-", "W-", "W-", "W^{-}"]),
+    (25, ["H", "Higgs0", "h0", "", "", "H^{0}"]),
+    (111, ["pi0", "pi0", "pi0", "pi0", "pi0", "\\pi^{0}"]),
+    (211, ["pi+", "pi+", "pi+", "pi+", "pi+", "\\pi^{+}"]),
+    (-211, ["pi-", "pi-", "pi-", "pi-", "pi-", "\\pi^{-}"]),
+    (221, ["eta", "eta", "eta", "eta", "eta", "\\eta"]),
+    (331, ["eta'(958)", "eta'", "eta'", "eta_prime", "eta'", "\\eta^{\\prime}(958)"]),
+    (113, ["rho(770)0", "rho0", "rho0", "", "rho770_0", "\\rho(770)^{0}"]),
+    (213, ["rho(770)+", "rho+", "rho+", "", "rho770_plus", "\\rho(770)^{+}"]),
+    (-213, ["rho(770)-", "rho-", "rho-", "", "rho770_minus", "\\rho(770)^{-}"]),
+    (223, ["omega(782)", "omega", "omega", "", "omega782", "\\omega(782)"]),
+    (333, ["phi(1020)", "phi", "phi", "", "phi1020", "\\phi(1020)"]),
+    (9010221, ["f(0)(980)", "f_0", "f0(980)", "", "", "f_{0}(980)"]),
+    (20213, ["a(1)(1260)+", "a_1+", "a_1(1260)+", "", "", "a_{1}(1260)^{+}"]),
+    (-20213, ["a(1)(1260)-", "a_1-", "a_1(1260)-", "", "", "a_{1}(1260)^{-}"]),
+    (321, ["K+", "K+", "K+", "kaon+", "K+", "K^{+}"]),
+    (-321, ["K-", "K-", "K-", "kaon-", "K-", "K^{-}"]),
+    (311, ["K0", "K0", "K0", "kaon0", "K0", "K^{0}"]),
+    (-311, ["Kbar0", "anti-K0", "Kbar0", "anti_kaon0", "K0_bar", "\\bar{K}^{0}"]),
+    (310, ["K(S)0", "K_S0", "K_S0", "kaon0S", "K_S0", "K^{0}_{S}"]),
+    (130, ["K(L)0", "K_L0", "K_L0", "kaon0L", "K_L0", "K^{0}_{L}"]),
+    (313, ["K*(892)0", "K*0", "K*0", "", "K*0", "K^{*}(892)^{0}"]),
+    (-313, ["K*(892)bar0", "anti-K*0", "K*bar0", "", "K*0_bar", "\\bar{K}^{*}(892)^{0}"]),
+    (323, ["K*(892)+", "K*+", "K*+", "", "K*+", "K^{*}(892)^{+}"]),
+    (-323, ["K*(892)-", "K*-", "K*-", "", "K*-", "K^{*}(892)^{-}"]),
+    (421, ["D0", "D0", "D0", "D0", "D0", "D^{0}"]),
+    (-421, ["Dbar0", "anti-D0", "Dbar0", "anti_D0", "D0_bar", "\\bar{D}^{0}"]),
+    (411, ["D+", "D+", "D+", "D+", "D+", "D^{+}"]),
+    (-411, ["D-", "D-", "D-", "D-", "D-", "D^{-}"]),
+    (431, ["D(s)+", "D_s+", "D_s+", "Ds+", "D_s+", "D_{s}^{+}"]),
+    (-431, ["D(s)-", "D_s-", "D_s-", "Ds-", "D_s-", "D_{s}^{-}"]),
+    (423, ["D*(2007)0", "D*0", "D*0", "", "D*0", "D^{*}(2007)^{0}"]),
+    (-423, ["D*(2007)bar0", "anti-D*0", "D*bar0", "", "D*0_bar", "\\bar{D}^{*}(2007)^{0}"]),
+    (413, ["D*(2010)+", "D*+", "D*+", "", "D*+", "D^{*}(2010)^{+}"]),
+    (-413, ["D*(2010)-", "D*-", "D*-", "", "D*-", "D^{*}(2010)^{-}"]),
+    (433, ["D(s)*+", "D_s*+", "D*_s+", "", "D*_s+", "D_{s}^{*+}"]),
+    (-433, ["D(s)*-", "D_s*-", "D*_s-", "", "D*_s-", "D_{s}^{*-}"]),
+    (443, ["J/psi(1S)", "J/psi", "J/psi", "J/psi", "J/psi", "J/\\psi(1S)"]),
+    (553, ["Upsilon(1S)", "Upsilon", "Upsilon", "Upsilon", "Upsilon", "\\Upsilon(1S)"]),
+    (511, ["B0", "B0", "B0", "B0", "B0", "B^{0}"]),
+    (-511, ["Bbar0", "anti-B0", "Bbar0", "anti_B0", "B0_bar", "\\bar{B}^{0}"]),
+    (521, ["B+", "B+", "B+", "B+", "B+", "B^{+}"]),
+    (-521, ["B-", "B-", "B-", "B-", "B-", "B^{-}"]),
+    (531, ["B(s)0", "B_s0", "B_s0", "Bs", "B_s0", "B_{s}^{0}"]),
+    (-531, ["B(s)bar0", "anti-B_s0", "B_sbar0", "anti_Bs", "B_s0_bar", "\\bar{B}_{s}^{0}"]),
+    (541, ["B(c)+", "B_c+", "B_c+", "Bc+", "B_c+", "B_{c}^{+}"]),
+    (-541, ["B(c)-", "B_c-", "B_c-", "Bc-", "B_c-", "B_{c}^{-}"]),
+    (2212, ["p", "p+", "p+", "proton", "proton", "p"]),
+    (-2212, ["pbar", "anti-p-", "pbar-", "anti_proton", "antiproton", "\\bar{p}"]),
+    (2112, ["n", "n0", "n0", "neutron", "neutron", "n"]),
+    (-2112, ["nbar", "anti-n0", "nbar0", "anti_neutron", "antineutron", "\\bar{n}"]),
+    (3122, ["Lambda", "Lambda0", "Lambda0", "lambda", "Lambda0", "\\Lambda"]),
+    (-3122, ["Lambdabar", "anti-Lambda0", "Lambdabar0", "anti_lambda", "Lambda0_bar", "\\bar{\\Lambda}"]),
+    (4122, ["Lambda(c)+", "Lambda_c+", "Lambda_c+", "lambda_c+", "Lambda_c+", "\\Lambda_{c}^{+}"]),
+    (5122, ["Lambda(b)0", "Lambda_b0", "Lambda_b0", "lambda_b", "Lambda_b0", "\\Lambda_{b}^{0}"]),
+    (3334, ["Omega-", "Omega-", "Omega-", "omega-", "Omega-", "\\Omega^{-}"]),
+];
+
+// Particle names in every convention keyed on the MC ID
+pub struct NameTable{
+    names: HashMap<i64, HashMap<Convention, String>>,
+}
+
+impl NameTable{
+    pub fn builtin() -> NameTable{
+        let mut names = HashMap::new();
+        for (mcid, row) in BUILTIN_NAMES.iter() {
+            let row = CONVENTIONS
+                .iter()
+                .zip(row.iter())
+                .filter(|(_, name)| !name.is_empty())
+                .map(|(convention, name)| (*convention, name.to_string()))
+                .collect::<HashMap<Convention, String>>();
+            names.insert(*mcid, row);
+        }
+        NameTable{ names }
+    }
+
+    // Lines of `<mcid> <convention> <name>`, which add to or replace the names of the table
+    pub fn load(&mut self, text: &str) -> Result<(), String>{
+        for (number, line) in text.lines().enumerate() {
+            let content = line.split('#').next().unwrap_or("").trim();
+            if content.is_empty() {
+                continue;
+            }
+            let fields = content.split_whitespace().collect::<Vec<&str>>();
+            let (mcid, convention, name) = match fields.as_slice() {
+                [mcid, convention, name] => (mcid, convention, name),
+                _ => return Err(format!("line {}: expected <mcid> <convention> <name>", number + 1)),
+            };
+            let mcid = mcid.parse::<i64>().map_err(|_| format!("line {}: invalid MC ID {}", number + 1, mcid))?;
+            let convention = convention.parse::<Convention>().map_err(|error| format!("line {}: {}", number + 1, error))?;
+            self.names.entry(mcid).or_default().insert(convention, name.to_string());
+        }
+        Ok(())
+    }
+
+    pub fn name(&self, mcid: i64, convention: Convention) -> Option<&str>{
+        self.names.get(&mcid)?.get(&convention).map(|name| name.as_str())
+    }
+
+    // The MC ID of a name in the given convention
+    pub fn mcid_in(&self, name: &str, convention: Convention) -> Option<i64>{
+        self.names
+            .iter()
+            .find(|(_, row)| row.get(&convention).is_some_and(|known| known == name))
+            .map(|(mcid, _)| *mcid)
+    }
+
+    // The MC ID of a name in any convention, the PDG name first
+    pub fn mcid(&self, name: &str) -> Option<i64>{
+        CONVENTIONS.iter().find_map(|convention| self.mcid_in(name, *convention))
+    }
+
+    pub fn translate(&self, name: &str, from: Convention, to: Convention) -> Option<&str>{
+        self.name(self.mcid_in(name, from)?, to)
+    }
+
+    pub fn render_channel(&self, channel: &DecayChannel, convention: Convention) -> DecayChannel{
+        let rename = |name: &str| self.translate(name, Convention::Pdg, convention).unwrap_or(name).to_string();
+        DecayChannel{
+            parent: rename(&channel.parent),
+            daughters: channel.daughters.iter().map(|(name, multiplicity)| (rename(name), *multiplicity)).collect(),
+            pdgid: channel.pdgid.clone(),
+        }
+    }
+
+    pub fn pdg_names(&self) -> impl Iterator<Item = &str>{
+        self.names.values().filter_map(|row| row.get(&Convention::Pdg)).map(|name| name.as_str())
+    }
+
+    // Every convention which has a name for the MC ID
+    pub fn all_names(&self, mcid: i64) -> Vec<(Convention, &str)>{
+        CONVENTIONS
+            .iter()
+            .filter_map(|convention| Some((*convention, self.name(mcid, *convention)?)))
+            .collect()
+    }
+}
+
+// The builtin table, overridden by the file at PDG_NAMES_PATH when it is set in the environment or .env
+pub fn name_table() -> &'static NameTable{
+    NAME_TABLE.get_or_init(|| {
+        let mut table = NameTable::builtin();
+        from_path(format!("{}/.env", env!("CARGO_MANIFEST_DIR"))).ok();
+        if let Ok(path) = std::env::var("PDG_NAMES_PATH") {
+            let loaded = std::fs::read_to_string(&path)
+                .map_err(|error| error.to_string())
+                .and_then(|text| table.load(&text));
+            if let Err(error) = loaded {
+                eprintln!("Cannot load particle names from {}: {}", path, error);
+            }
+        }
+        table
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_names(){
+        let table = NameTable::builtin();
+        assert_eq!(table.name(310, Convention::EvtGen), Some("K_S0"));
+        assert_eq!(table.name(310, Convention::Geant4), Some("kaon0S"));
+        assert_eq!(table.translate("J/psi(1S)", Convention::Pdg, Convention::Latex), Some("J/\\psi(1S)"));
+        assert_eq!(table.translate("anti-D0", Convention::EvtGen, Convention::Root), Some("D0_bar"));
+        assert_eq!(table.mcid("anti_proton"), Some(-2212));
+        assert_eq!(table.mcid("Lambda_c+"), Some(4122));
+        assert_eq!(table.name(113, Convention::Geant4), None);
+    }
+
+    #[test]
+    fn test_names_are_unambiguous(){
+        // a name may only stand for one MC ID, whatever the convention
+        let table = NameTable::builtin();
+        let mut seen: HashMap<&str, i64> = HashMap::new();
+        for (mcid, row) in table.names.iter() {
+            for name in row.values() {
+                if let Some(other) = seen.insert(name.as_str(), *mcid) {
+                    assert_eq!(other, *mcid, "{} is used for {} and {}", name, other, mcid);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_load(){
+        let mut table = NameTable::builtin();
+        table.load("# local names\n310 evtgen KS\n9000111 pythia a0_light\n").unwrap();
+        assert_eq!(table.name(310, Convention::EvtGen), Some("KS"));
+        assert_eq!(table.name(310, Convention::Pdg), Some("K(S)0"));
+        assert_eq!(table.mcid("a0_light"), Some(9000111));
+        assert!(table.load("310 fluka KS").unwrap_err().starts_with("line 1"));
+        assert!(table.load("310 evtgen").is_err());
+    }
+
+    #[test]
+    fn test_render_channel(){
+        let table = NameTable::builtin();
+        let channel = DecayChannel{
+            parent: "D0".to_string(),
+            daughters: HashMap::from([("K(S)0".to_string(), 1), ("pi0".to_string(), 2), ("unknown".to_string(), 1)]),
+            pdgid: "S032.1".to_string(),
+        };
+        let rendered = table.render_channel(&channel, Convention::Geant4);
+        assert_eq!(rendered.parent, "D0");
+        assert_eq!(rendered.daughters.get("kaon0S"), Some(&1));
+        assert_eq!(rendered.daughters.get("pi0"), Some(&2));
+        assert_eq!(rendered.daughters.get("unknown"), Some(&1));
+    }
+
+    #[test]
+    fn test_convention_from_str(){
+        assert_eq!("evtgen".parse::<Convention>(), Ok(Convention::EvtGen));
+        assert_eq!("LaTeX".parse::<Convention>(), Ok(Convention::Latex));
+        assert!("fluka".parse::<Convention>().is_err());
+    }
+}
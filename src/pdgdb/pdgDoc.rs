@@ -0,0 +1,156 @@
+// The pdgdoc table documents the codes of the other tables, i.e., limit_type "U" of pdgdata is an upper limit.
+// It is read with each connection and shared by the particles of its queries.
+use std::collections::HashMap;
+use super::dataType::DataType;
+
+// pdgdata.limit_type decoded, pdgdoc only gives the meaning that is printed
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LimitType{
+    Upper, // U
+    Lower, // L
+    Range, // R
+}
+
+impl LimitType{
+    pub fn decode(code: &str) -> Option<LimitType>{
+        match code {
+            "U" => Some(LimitType::Upper),
+            "L" => Some(LimitType::Lower),
+            "R" => Some(LimitType::Range),
+            _ => None,
+        }
+    }
+
+    pub fn code(&self) -> &'static str{
+        match self {
+            LimitType::Upper => "U",
+            LimitType::Lower => "L",
+            LimitType::Range => "R",
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct PdgDoc{
+    codes: HashMap<(String, String, String), String>, // (table, column, code) to the meaning, table and column in lower case
+}
+
+impl PdgDoc{
+    // An empty documentation for databases without pdgdoc, codes are then printed as they are
+    pub fn load(conn: &rusqlite::Connection) -> PdgDoc{
+        let mut stmt = match conn.prepare("SELECT table_name, column_name, value, description FROM pdgdoc") {
+            Ok(stmt) => stmt,
+            Err(_) => return PdgDoc::default(),
+        };
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                row.get::<_, Option<String>>(0)?,
+                row.get::<_, Option<String>>(1)?,
+                row.get::<_, Option<String>>(2)?,
+                row.get::<_, Option<String>>(3)?,
+            ))
+        });
+        let codes = match rows {
+            Ok(rows) => rows
+                .filter_map(|row| row.ok())
+                .filter_map(|(table, column, code, description)| {
+                    Some(((table?.to_lowercase(), column?.to_lowercase(), code?), description?))
+                })
+                .collect(),
+            Err(_) => HashMap::new(),
+        };
+        PdgDoc{ codes }
+    }
+
+    pub fn is_empty(&self) -> bool{
+        self.codes.is_empty()
+    }
+
+    // Official meaning of a code of table.column, table and column in any case
+    pub fn meaning(&self, table: &str, column: &str, code: &str) -> Option<&str>{
+        self.codes
+            .get(&(table.to_lowercase(), column.to_lowercase(), code.to_string()))
+            .map(|meaning| meaning.as_str())
+    }
+
+    // pdgdata.limit_type, i.e., "U", "L" or "R"
+    pub fn limit_type(&self, code: &str) -> Option<&str>{
+        self.meaning("pdgdata", "limit_type", code)
+    }
+
+    pub fn data_type(&self, code: &str) -> DataType{
+        DataType::decode(code, self)
+    }
+
+    // pdgid.flags with their meaning, documented as a whole or otherwise letter by letter, undocumented letters
+    // are left out
+    pub fn flags(&self, flags: &str) -> Vec<(String, String)>{
+        if let Some(meaning) = self.meaning("pdgid", "flags", flags) {
+            return vec![(flags.to_string(), meaning.to_string())];
+        }
+        flags
+            .chars()
+            .filter(|flag| !flag.is_whitespace())
+            .filter_map(|flag| {
+                let flag = flag.to_string();
+                let meaning = self.meaning("pdgid", "flags", &flag)?.to_string();
+                Some((flag, meaning))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_doc() -> PdgDoc{
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE pdgdoc (id INTEGER, table_name TEXT, column_name TEXT, value TEXT, indicator TEXT, description TEXT, comment TEXT);
+             INSERT INTO pdgdoc VALUES (1, 'PDGID', 'DATA_TYPE', 'MM', NULL, 'Magnetic moment', NULL);
+             INSERT INTO pdgdoc VALUES (2, 'PDGDATA', 'LIMIT_TYPE', 'U', NULL, 'Upper limit', NULL);
+             INSERT INTO pdgdoc VALUES (3, 'PDGDATA', 'LIMIT_TYPE', 'L', NULL, 'Lower limit', NULL);
+             INSERT INTO pdgdoc VALUES (4, 'PDGID', 'FLAGS', 'D', NULL, 'Data in a footnote', NULL);
+             INSERT INTO pdgdoc VALUES (5, 'PDGID', 'FLAGS', 'X', NULL, NULL, NULL);",
+        ).unwrap();
+        PdgDoc::load(&conn)
+    }
+
+    #[test]
+    fn test_load(){
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        assert!(PdgDoc::load(&conn).is_empty());
+        let doc = test_doc();
+        assert_eq!(doc.limit_type("U"), Some("Upper limit"));
+        assert_eq!(doc.meaning("pdgdata", "LIMIT_TYPE", "L"), Some("Lower limit"));
+        assert_eq!(doc.limit_type("R"), None);
+    }
+
+    #[test]
+    fn test_limit_type(){
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE pdgdoc (id INTEGER, table_name TEXT, column_name TEXT, value TEXT, indicator TEXT, description TEXT, comment TEXT);
+             INSERT INTO pdgdoc VALUES (1, 'PDGDATA', 'LIMIT_TYPE', 'U', NULL, 'Lower limit', NULL);",
+        ).unwrap();
+        // the code decides the kind of limit, a misleading description only changes what is printed
+        let doc = PdgDoc::load(&conn);
+        assert_eq!(LimitType::decode("U"), Some(LimitType::Upper));
+        assert_eq!(doc.limit_type("U"), Some("Lower limit"));
+        assert_eq!(LimitType::decode("R"), Some(LimitType::Range));
+        assert_eq!(LimitType::decode("E"), None);
+        assert_eq!(LimitType::Lower.code(), "L");
+    }
+
+    #[test]
+    fn test_data_type_and_flags(){
+        let doc = test_doc();
+        assert_eq!(doc.data_type("M"), DataType::Mass);
+        assert_eq!(doc.data_type("MM").section(), "Magnetic moment");
+        let footnote = ("D".to_string(), "Data in a footnote".to_string());
+        assert_eq!(doc.flags("D"), vec![footnote.clone()]);
+        assert_eq!(doc.flags("DX"), vec![footnote]);
+        assert!(doc.flags("").is_empty());
+    }
+}
@@ -0,0 +1,222 @@
+// Values with PDG units, i.e., "GeV", "s", "fm", "mu(N)" or "GeV**-2", converted within a dimension and,
+// with ħ and c, between energies, times and lengths. A width becomes a lifetime ħ/Γ or a decay length ħc/Γ.
+use super::{ParticleMeasurement, HBAR_GEV_S};
+
+const HBARC_GEV_FM: f64 = 0.1973269804; // ħc in GeV fm
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Dimension{
+    Energy,
+    Time,
+    Length,
+    NuclearMagneton,
+    BohrMagneton,
+    Dimensionless,
+}
+
+// Symbol, dimension and size in GeV, s or fm
+const SYMBOLS: [(&str, Dimension, f64); 24] = [
+    ("eV", Dimension::Energy, 1e-9),
+    ("keV", Dimension::Energy, 1e-6),
+    ("MeV", Dimension::Energy, 1e-3),
+    ("GeV", Dimension::Energy, 1.0),
+    ("TeV", Dimension::Energy, 1e3),
+    ("s", Dimension::Time, 1.0),
+    ("ms", Dimension::Time, 1e-3),
+    ("us", Dimension::Time, 1e-6),
+    ("ns", Dimension::Time, 1e-9),
+    ("ps", Dimension::Time, 1e-12),
+    ("fs", Dimension::Time, 1e-15),
+    ("yr", Dimension::Time, 3.15576e7),
+    ("years", Dimension::Time, 3.15576e7),
+    ("fm", Dimension::Length, 1.0),
+    ("nm", Dimension::Length, 1e6),
+    ("um", Dimension::Length, 1e9),
+    ("micrometers", Dimension::Length, 1e9),
+    ("mm", Dimension::Length, 1e12),
+    ("cm", Dimension::Length, 1e13),
+    ("m", Dimension::Length, 1e15),
+    ("km", Dimension::Length, 1e18),
+    ("mu(N)", Dimension::NuclearMagneton, 1.0),
+    ("mu(B)", Dimension::BohrMagneton, 1.0),
+    ("", Dimension::Dimensionless, 1.0),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Unit{
+    pub symbol: &'static str,
+    pub dimension: Dimension,
+    pub scale: f64, // size of the symbol in GeV, s or fm
+    pub power: i32,
+}
+
+impl Unit{
+    // PDG unit text, masses in GeV/c**2 are energies as c is one
+    pub fn parse(text: &str) -> Option<Unit>{
+        let text = text.trim();
+        let text = text.strip_suffix("/c**2").or_else(|| text.strip_suffix("/c")).unwrap_or(text);
+        let (symbol, power) = match text.split_once("**") {
+            Some((symbol, power)) => (symbol, power.parse::<i32>().ok()?),
+            None => (text, 1),
+        };
+        SYMBOLS
+            .iter()
+            .find(|(name, _, _)| *name == symbol)
+            .map(|(symbol, dimension, scale)| Unit{ symbol, dimension: *dimension, scale: *scale, power })
+    }
+
+    // Size in powers of GeV with ħ = c = 1, None for units that ħ and c do not relate to energies
+    fn natural(&self) -> Option<(f64, i32)>{
+        match self.dimension {
+            Dimension::Energy => Some((self.scale.powi(self.power), self.power)),
+            Dimension::Time => Some(((self.scale / HBAR_GEV_S).powi(self.power), -self.power)),
+            Dimension::Length => Some(((self.scale / HBARC_GEV_FM).powi(self.power), -self.power)),
+            _ => None,
+        }
+    }
+
+    // The target measures the inverse, i.e., a width written as a lifetime, so errors and limits swap sides
+    pub fn inverts(&self, target: &Unit) -> bool{
+        match (self.natural(), target.natural()) {
+            (Some((_, from)), Some((_, to))) => from != 0 && from == -to,
+            _ => false,
+        }
+    }
+
+    // x in this unit written in the target unit, None when they do not measure the same thing
+    pub fn convert(&self, x: f64, target: &Unit) -> Option<f64>{
+        if self.dimension == target.dimension && self.power == target.power {
+            return Some(x * (self.scale / target.scale).powi(self.power));
+        }
+        let ((from_factor, from), (to_factor, to)) = (self.natural()?, target.natural()?);
+        if from == to {
+            Some(x * from_factor / to_factor)
+        } else if self.inverts(target) {
+            Some(1.0 / (x * from_factor) / to_factor)
+        } else {
+            None
+        }
+    }
+}
+
+impl std::fmt::Display for Unit{
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result{
+        match self.power {
+            1 => write!(f, "{}", self.symbol),
+            power => write!(f, "{}**{}", self.symbol, power),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Quantity{
+    pub value: f64,
+    pub plus_error: Option<f64>, // infinite when the side is unbounded
+    pub minus_error: Option<f64>,
+    pub unit: Unit,
+}
+
+impl Quantity{
+    // None without a value or with a unit that does not parse
+    pub fn from_measurement(measurement: &ParticleMeasurement) -> Option<Quantity>{
+        Some(Quantity{
+            value: measurement.value?,
+            plus_error: measurement.plus_error,
+            minus_error: measurement.minus_error,
+            unit: Unit::parse(measurement.unit_text.as_deref().unwrap_or(""))?,
+        })
+    }
+
+    // Errors are converted through the ends of the interval, so they stay exact under 1/x. An end at or below zero
+    // has no inverse, i.e., a width compatible with zero is a lifetime without an upper bound, so that side is infinite.
+    pub fn convert(&self, target: &Unit) -> Option<Quantity>{
+        let value = self.unit.convert(self.value, target)?;
+        let inverts = self.unit.inverts(target);
+        let end = |error: Option<f64>, sign: f64| {
+            let end = self.value + sign * error?;
+            if inverts && end <= 0.0 {
+                return Some(f64::INFINITY);
+            }
+            self.unit.convert(end, target).map(|end| (end - value).abs())
+        };
+        let (plus_error, minus_error) = if inverts {
+            (end(self.minus_error, -1.0), end(self.plus_error, 1.0))
+        } else {
+            (end(self.plus_error, 1.0), end(self.minus_error, -1.0))
+        };
+        Some(Quantity{ value, plus_error, minus_error, unit: *target })
+    }
+
+    // In GeV, s or fm
+    pub fn normalized(&self) -> Quantity{
+        let symbol = SYMBOLS
+            .iter()
+            .find(|(_, dimension, scale)| *dimension == self.unit.dimension && *scale == 1.0)
+            .map_or(self.unit.symbol, |(symbol, _, _)| symbol);
+        self.convert(&Unit{ symbol, scale: 1.0, ..self.unit }).unwrap_or(*self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unit(text: &str) -> Unit{
+        Unit::parse(text).unwrap()
+    }
+
+    fn close(a: f64, b: f64) -> bool{
+        (a - b).abs() <= 1e-9 * b.abs()
+    }
+
+    #[test]
+    fn test_parse(){
+        assert_eq!(unit("GeV**-2"), Unit{ symbol: "GeV", dimension: Dimension::Energy, scale: 1.0, power: -2 });
+        assert_eq!(unit("GeV/c**2").dimension, Dimension::Energy);
+        assert_eq!(unit("mu(N)").dimension, Dimension::NuclearMagneton);
+        assert_eq!(unit("fm**3").to_string(), "fm**3");
+        assert_eq!(unit("MeV").to_string(), "MeV");
+        assert_eq!(Unit::parse("GeV**-1/2"), None);
+        assert_eq!(Unit::parse("furlong"), None);
+    }
+
+    #[test]
+    fn test_convert_within_dimension(){
+        assert!(close(unit("GeV").convert(1.77686, &unit("MeV")).unwrap(), 1776.86));
+        assert!(close(unit("s").convert(2.903e-13, &unit("ps")).unwrap(), 0.2903));
+        assert!(close(unit("GeV**-2").convert(1.0, &unit("MeV**-2")).unwrap(), 1e-6));
+        assert_eq!(unit("mu(N)").convert(1.0, &unit("GeV")), None);
+        assert_eq!(unit("GeV").convert(1.0, &unit("GeV**2")), None);
+    }
+
+    #[test]
+    fn test_convert_with_hbar_and_c(){
+        // muon width 2.99598e-19 GeV is a lifetime of 2.1969811e-6 s and a c tau of 658.6 m
+        let width = unit("GeV");
+        assert!(width.inverts(&unit("s")));
+        assert!((width.convert(2.99598e-19, &unit("s")).unwrap() - 2.19698e-6).abs() < 1e-11);
+        assert!((width.convert(2.99598e-19, &unit("m")).unwrap() - 658.6).abs() < 0.1);
+        // the proton radius 0.84 fm as an inverse energy
+        assert!((unit("fm").convert(0.84, &unit("GeV**-1")).unwrap() - 4.257).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_quantity_errors(){
+        let width = Quantity{ value: 2.0, plus_error: Some(1.0), minus_error: Some(1.0), unit: unit("GeV") };
+        let mass = width.convert(&unit("MeV")).unwrap();
+        assert!(close(mass.value, 2000.0) && close(mass.plus_error.unwrap(), 1000.0));
+        // as a lifetime the larger error comes from the lower end of the width
+        let lifetime = width.convert(&unit("s")).unwrap();
+        assert!(close(lifetime.value, HBAR_GEV_S / 2.0));
+        assert!(close(lifetime.plus_error.unwrap(), HBAR_GEV_S / 1.0 - HBAR_GEV_S / 2.0));
+        assert!(close(lifetime.minus_error.unwrap(), HBAR_GEV_S / 2.0 - HBAR_GEV_S / 3.0));
+        // the width reaches zero, the lifetime has no upper bound
+        let width = Quantity{ value: 2.0, plus_error: Some(1.0), minus_error: Some(2.5), unit: unit("GeV") };
+        let lifetime = width.convert(&unit("s")).unwrap();
+        assert_eq!(lifetime.plus_error, Some(f64::INFINITY));
+        assert!(close(lifetime.minus_error.unwrap(), HBAR_GEV_S / 2.0 - HBAR_GEV_S / 3.0));
+        let normalized = Quantity{ value: 2.9e-13, plus_error: None, minus_error: None, unit: unit("ps") }.normalized();
+        assert_eq!(normalized.unit.symbol, "s");
+        assert!(close(normalized.value, 2.9e-25));
+    }
+}
@@ -1,21 +1,27 @@
 use std::collections::{HashMap, HashSet};
 
-use rusqlite::{Connection, Result};
+use rusqlite::Result;
 use crate::{cli::parser::{query_type_classifier, QueryType}, pdgdb::DecayChannel};
 
-use crate::pdgdb::connection::connect;
+use crate::pdgdb::connection::PdgConnection;
+use crate::pdgdb::pdgDoc::PdgDoc;
 
 
 pub struct DecayQuery{
-    conn: Connection,
+    conn: PdgConnection,
 }
 
 impl DecayQuery{
     pub fn new() -> Self{
         DecayQuery{
-            conn: connect().expect("Error connecting to database"),
+            conn: PdgConnection::open().expect("Error connecting to database"),
         }
     }
+
+    // Meaning of the codes of the database, shared with the particle queries
+    pub fn doc(&self) -> &PdgDoc{
+        self.conn.doc()
+    }
     
     pub fn get_decays_inclusive(&self, args: &[&str]) -> Result<Vec<String>>{
         let where_clause = DecayQuery::where_clause_formatter(args);
@@ -138,6 +144,7 @@ impl DecayQuery{
             }
             QueryType::DecayWildcard | QueryType::ParentlessDecayWildcard => format!(">={}", num_particles),
             QueryType::SingleParticle => panic!("Single particle query not supported"),
+            QueryType::ConservationCheck | QueryType::DecayTree | QueryType::DecaySample | QueryType::Export | QueryType::SlhaCompare | QueryType::Validate | QueryType::Report | QueryType::Cite => panic!("Not a decay search"),
             QueryType::Unknown => panic!("Unknown query type"),
         }
     }   
@@ -0,0 +1,228 @@
+use std::collections::HashMap;
+
+use crate::pdgdb::queries::decayQueries::DecayQuery;
+use crate::pdgdb::queries::singleQueries::ParticleQuery;
+use crate::pdgdb::Particle;
+
+// Particles that are never expanded unless they are the root of the tree
+const DEFAULT_STABLE: [&str; 20] = [
+    "e-", "e+", "mu-", "mu+", "gamma", "nu_e", "nubar_e", "nu_mu", "nubar_mu", "nu_tau", "nubar_tau",
+    "pi+", "pi-", "K+", "K-", "K(L)0", "p", "pbar", "n", "nbar",
+];
+
+pub struct DecayTreeConfig{
+    pub max_depth: usize, // number of decay steps below the root
+    pub min_probability: f64, // branches with a smaller product of branching fractions are dropped
+    pub ctau_threshold: Option<f64>, // in mm, particles flying further are considered stable
+    pub stable_particles: Vec<String>,
+}
+
+impl Default for DecayTreeConfig{
+    fn default() -> Self{
+        DecayTreeConfig{
+            max_depth: 3,
+            min_probability: 1e-3,
+            ctau_threshold: Some(10.0),
+            stable_particles: DEFAULT_STABLE.iter().map(|name| name.to_string()).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DecayNode{
+    pub particle: String,
+    pub mcid: Option<i64>,
+    pub charge: Option<f64>,
+    pub mass: Option<f64>, // GeV
+    pub probability: f64, // product of the branching fractions from the root down to this particle
+    pub channels: Vec<DecayBranch>, // empty for particles treated as stable
+    pub truncated: bool, // decays, but the depth or probability limit left no channel of it
+}
+
+#[derive(Debug, Clone)]
+pub struct DecayBranch{
+    pub node_id: String, // pdgid of the decay mode, i.e., S035.1
+    pub branching_fraction: f64,
+    pub plus_error: Option<f64>,
+    pub minus_error: Option<f64>,
+    pub daughters: Vec<DecayNode>,
+}
+
+impl DecayNode{
+    pub fn leaf(particle: &str, mcid: Option<i64>, charge: Option<f64>, probability: f64) -> Self{
+        DecayNode{
+            particle: particle.to_string(),
+            mcid,
+            charge,
+            mass: None,
+            probability,
+            channels: Vec::new(),
+            truncated: false,
+        }
+    }
+
+    // Not decayed in the tree, either stable or truncated
+    pub fn is_leaf(&self) -> bool{
+        self.channels.is_empty()
+    }
+
+    pub fn is_stable(&self) -> bool{
+        self.is_leaf() && !self.truncated
+    }
+}
+
+// What the tree needs to know about a particle, cached since the same daughters appear many times
+#[derive(Clone)]
+struct ParticleSummary{
+    mcid: Option<i64>,
+    charge: Option<f64>,
+    mass: Option<f64>,
+    ctau: Option<f64>,
+    decays: Vec<(String, f64, Option<f64>, Option<f64>)>, // node_id, value, plus_error, minus_error
+}
+
+pub struct DecayTreeBuilder{
+    particle_query: ParticleQuery,
+    decay_query: DecayQuery,
+    config: DecayTreeConfig,
+    cache: HashMap<String, Option<ParticleSummary>>,
+    // sorted daughter names of every decay mode looked up, the same mode is reached through many parents
+    channels: HashMap<String, Option<Vec<String>>>,
+}
+
+impl DecayTreeBuilder{
+    pub fn new(config: DecayTreeConfig) -> Self{
+        DecayTreeBuilder{
+            particle_query: ParticleQuery::new(),
+            decay_query: DecayQuery::new(),
+            config,
+            cache: HashMap::new(),
+            channels: HashMap::new(),
+        }
+    }
+
+    pub fn build(&mut self, name: &str) -> Option<DecayNode>{
+        self.summary(name)?;
+        Some(self.expand(name, 1.0, 0))
+    }
+
+    fn summary(&mut self, name: &str) -> Option<ParticleSummary>{
+        if let Some(summary) = self.cache.get(name) {
+            return summary.clone();
+        }
+        let summary = self.particle_query.query(name).map(|particle| DecayTreeBuilder::summarise(&particle));
+        self.cache.insert(name.to_string(), summary.clone());
+        summary
+    }
+
+    fn summarise(particle: &Particle) -> ParticleSummary{
+        let decays = particle.decay
+            .iter()
+            .flatten()
+            .filter(|decay| decay.is_exclusive_measurement())
+            .filter_map(|decay| Some((decay.node_id.clone()?, decay.value?, decay.plus_error, decay.minus_error)))
+            .collect();
+        ParticleSummary{
+            mcid: particle.pdgid,
+            charge: particle.charge,
+            mass: particle.mass,
+            ctau: particle.ctau(),
+            decays,
+        }
+    }
+
+    // None when the mode cannot be mapped or has no daughters
+    fn daughter_names(&mut self, node_id: &str) -> Option<Vec<String>>{
+        if let Some(names) = self.channels.get(node_id) {
+            return names.clone();
+        }
+        let names = self.decay_query.map_decay(node_id).ok().and_then(|channel| {
+            let mut names = channel.daughters
+                .iter()
+                .flat_map(|(daughter, multiplicity)| std::iter::repeat_n(daughter.clone(), *multiplicity as usize))
+                .collect::<Vec<String>>();
+            names.sort();
+            (!names.is_empty()).then_some(names)
+        });
+        self.channels.insert(node_id.to_string(), names.clone());
+        names
+    }
+
+    fn is_stable(&self, name: &str, summary: &ParticleSummary) -> bool{
+        let long_lived = match (summary.ctau, self.config.ctau_threshold) {
+            (Some(ctau), Some(threshold)) => ctau >= threshold,
+            _ => false,
+        };
+        self.config.stable_particles.iter().any(|stable| stable == name) || long_lived
+    }
+
+    fn expand(&mut self, name: &str, probability: f64, depth: usize) -> DecayNode{
+        let summary = match self.summary(name) {
+            Some(summary) => summary,
+            // names like "hadrons" have no pdgparticle entry, they stay as they are
+            None => return DecayNode::leaf(name, None, None, probability),
+        };
+        let mut node = DecayNode::leaf(name, summary.mcid, summary.charge, probability);
+        node.mass = summary.mass;
+        if depth > 0 && self.is_stable(name, &summary) {
+            return node;
+        }
+        if depth >= self.config.max_depth {
+            node.truncated = !summary.decays.is_empty();
+            return node;
+        }
+
+        for (node_id, value, plus_error, minus_error) in summary.decays.iter() {
+            let branch_probability = probability * value;
+            if branch_probability < self.config.min_probability {
+                continue;
+            }
+            let daughter_names = match self.daughter_names(node_id) {
+                Some(names) => names,
+                None => continue,
+            };
+            let daughters = daughter_names
+                .iter()
+                .map(|daughter| self.expand(daughter, branch_probability, depth + 1))
+                .collect();
+            node.channels.push(DecayBranch{
+                node_id: node_id.clone(),
+                branching_fraction: *value,
+                plus_error: *plus_error,
+                minus_error: *minus_error,
+                daughters,
+            });
+        }
+        // every channel below the probability limit, the particle still decays
+        node.truncated = node.channels.is_empty() && !summary.decays.is_empty();
+        node
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_decay_tree(){
+        let mut builder = DecayTreeBuilder::new(DecayTreeConfig::default());
+        let tree = builder.build("D*(2010)+").unwrap();
+        assert!(!tree.channels.is_empty());
+        let d0_channel = tree.channels
+            .iter()
+            .find(|channel| channel.daughters.iter().any(|daughter| daughter.particle == "D0"))
+            .unwrap();
+        let d0 = d0_channel.daughters.iter().find(|daughter| daughter.particle == "D0").unwrap();
+        assert!(!d0.is_stable());
+        assert!((d0.probability - d0_channel.branching_fraction).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_stable_root_is_expanded(){
+        let config = DecayTreeConfig{ max_depth: 1, ..DecayTreeConfig::default() };
+        let mut builder = DecayTreeBuilder::new(config);
+        let tree = builder.build("pi+").unwrap();
+        assert!(!tree.channels.is_empty());
+        assert!(tree.channels.iter().all(|channel| channel.daughters.iter().all(|daughter| daughter.is_stable())));
+    }
+}
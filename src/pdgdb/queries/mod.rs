@@ -1,3 +1,4 @@
 pub mod common;
 pub mod singleQueries;
-pub mod decayQueries;
\ No newline at end of file
+pub mod decayQueries;
+pub mod decayTree;
\ No newline at end of file
@@ -1,15 +1,18 @@
 use crate::pdgdb::Particle;
-use crate::pdgdb::connection::connect;
-use rusqlite::{Connection, Result};
+use crate::pdgdb::pdgDoc::PdgDoc;
+use crate::pdgdb::listings::{find_listings, Listing};
+use crate::pdgdb::references::{find_edition, find_references, Edition, Reference};
+use crate::pdgdb::connection::PdgConnection;
+use crate::pdgdb::naming::name_table;
+use rusqlite::Result;
+use std::sync::Arc;
 pub struct ParticleQuery{
-    conn: Connection
+    conn: PdgConnection,
 }
 
 impl ParticleQuery{
     pub fn new()->Self{
-        ParticleQuery{
-            conn: connect().unwrap(),
-        }
+        ParticleQuery{ conn: PdgConnection::open().unwrap() }
     }
     pub fn query(&self, args:&str) -> Option<Particle>{
         if let Ok(id) = args.parse::<i64>(){
@@ -24,9 +27,54 @@ impl ParticleQuery{
         if let Ok(particle) = self.get_by_node_id(&args){
             return Some(particle);
         }
+        // EvtGen, Pythia, Geant4, ROOT or LaTeX names
+        if let Some(mcid) = name_table().mcid(args){
+            return self.get_by_id(mcid).ok();
+        }
         None
     }
 
+    // Meaning of the codes of the database, i.e., doc().limit_type("U")
+    pub fn doc(&self) -> &PdgDoc {
+        self.conn.doc()
+    }
+
+    // The individual measurements behind a summary value, i.e., "S035M" for the tau mass
+    pub fn listings(&self, node_id: &str) -> Result<Vec<Listing>> {
+        find_listings(&self.conn, node_id)
+    }
+
+    // The papers behind a value, i.e., "S035M", or behind every value of a particle, i.e., "S035"
+    pub fn references(&self, node_id: &str, whole_particle: bool) -> Result<Vec<Reference>> {
+        find_references(&self.conn, node_id, whole_particle)
+    }
+
+    pub fn edition(&self) -> Result<Edition> {
+        find_edition(&self.conn)
+    }
+
+    // Only the MC ID of a name, without loading decays and measurements
+    pub fn get_mcid(&self, name: &str) -> Option<i64> {
+        self.conn
+            .query_row("SELECT mcid FROM pdgparticle WHERE name = ?1", [name], |row| row.get::<_, Option<i64>>(0))
+            .ok()
+            .flatten()
+    }
+
+    // Only the name of an MC ID, i.e., to find the antiparticle
+    pub fn get_name(&self, mcid: i64) -> Option<String> {
+        self.conn
+            .query_row("SELECT name FROM pdgparticle WHERE mcid = ?1", [mcid], |row| row.get(0))
+            .ok()
+    }
+
+    // Names of every particle with an MC ID, particles before their antiparticles
+    pub fn all_names(&self) -> Result<Vec<String>> {
+        let mut stmt = self.conn.prepare("SELECT name FROM pdgparticle WHERE mcid IS NOT NULL ORDER BY abs(mcid), mcid DESC")?;
+        let names = stmt.query_map([], |row| row.get(0))?.collect::<Result<Vec<String>>>()?;
+        Ok(names)
+    }
+
     fn get_by_id(&self, pdgid: i64) -> Result<Particle> {
         let stmt = &mut self.conn.prepare("SELECT * FROM pdgparticle WHERE mcid = ?1")?;
         // This line is very complicated,
@@ -35,25 +83,28 @@ impl ParticleQuery{
         // The second argument is a closure, it is called after receiving return value from the query_row method
         // It is called not because of the syntax, but because of the query_row method
         let mut particle = stmt.query_row(&[&pdgid], |row| ParticleQuery::map_particle(row))?; 
-        particle.find_decay(&self.conn);
-        particle.find_measurement(&self.conn);
+        self.find_details(&mut particle);
     Ok(particle)
     }
     
     fn get_by_name(&self, name: &str) -> Result<Particle> {
         let mut stmt = &mut self.conn.prepare("SELECT * FROM pdgparticle WHERE name = ?1")?;
         let mut particle = stmt.query_row(&[&name], |row| ParticleQuery::map_particle(row))?;
-        particle.find_decay(&self.conn);
-        particle.find_measurement(&self.conn);
+        self.find_details(&mut particle);
         Ok(particle)
     }
 
     fn get_by_node_id(&self, node_id: &str) -> Result<Particle> {
         let mut stmt = &mut self.conn.prepare("SELECT * FROM pdgparticle WHERE pdgid = ?1")?;
         let mut particle = stmt.query_row(&[&node_id], |row| ParticleQuery::map_particle(row))?;
+        self.find_details(&mut particle);
+        Ok(particle)
+    }
+
+    fn find_details(&self, particle: &mut Particle) {
+        particle.doc = Arc::clone(self.conn.doc());
         particle.find_decay(&self.conn);
         particle.find_measurement(&self.conn);
-        Ok(particle)
     }
 
     fn map_particle(row: &rusqlite::Row) -> Result<Particle> {
@@ -76,6 +127,8 @@ impl ParticleQuery{
         pdgitem_id: row.get("pdgitem_id")?,
         decay: None,
         measurements: None,
+        doc: Arc::default(),
+        footnotes: Vec::new(),
         };
         Ok(particle)
     }
@@ -101,6 +154,15 @@ mod tests {
         assert_eq!(particle.pdgitem_id, Some(76395));
     }
 
+    #[test]
+    fn test_get_mcid_and_name(){
+        let query = ParticleQuery::new();
+        assert_eq!(query.get_mcid("rho_3(1690)0"), Some(117));
+        assert_eq!(query.get_name(117), Some("rho_3(1690)0".to_string()));
+        assert_eq!(query.get_mcid("not a particle"), None);
+        assert!(query.all_names().unwrap().contains(&"rho_3(1690)0".to_string()));
+    }
+
     #[test]
     fn test_get_particle_by_name(){
         let query = ParticleQuery::new();
@@ -0,0 +1,137 @@
+// Papers behind the PDG values, pdgreference linked from pdgmeasurement, and the PDG edition itself from pdginfo
+use rusqlite::Result;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reference{
+    pub id: i64, // id in pdgreference
+    pub document_id: Option<String>, // first author and year, i.e., "ABLIKIM 2014D"
+    pub publication_name: Option<String>, // journal, volume and page as the PDG abbreviates them, i.e., "PR D90 012001"
+    pub year: Option<i64>, // publication_year
+    pub doi: Option<String>,
+    pub inspire_id: Option<String>,
+    pub title: Option<String>,
+}
+
+impl Reference{
+    // The surname of the first author from the document id, "ABLIKIM 2014D" is by Ablikim
+    pub fn first_author(&self) -> Option<String>{
+        let surname = self.document_id.as_deref()?.split_whitespace().next()?;
+        let mut letters = surname.chars();
+        let first = letters.next()?;
+        Some(first.to_uppercase().chain(letters.flat_map(|letter| letter.to_lowercase())).collect())
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Edition{
+    pub edition: Option<String>, // "edition" in pdginfo, i.e., "2024"
+    pub citation: Option<String>, // "citation" in pdginfo, the reference of the Review of Particle Physics
+}
+
+// pdginfo holds name and value pairs
+pub fn find_edition(conn: &rusqlite::Connection) -> Result<Edition>{
+    let mut stmt = conn.prepare("SELECT name, value FROM pdginfo WHERE name IN ('edition', 'citation')")?;
+    let mut edition = Edition::default();
+    for row in stmt.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, Option<String>>(1)?)))? {
+        match row? {
+            (name, value) if name == "edition" => edition.edition = value,
+            (_, value) => edition.citation = value,
+        }
+    }
+    Ok(edition)
+}
+
+// References of the measurements of a value, i.e., "S035M", or of every value of a particle, i.e., "S035",
+// newest first. The values of a particle continue its id with a letter or a dot, "S035.1" is not a value of "S035.10"
+pub fn find_references(conn: &rusqlite::Connection, node_id: &str, whole_particle: bool) -> Result<Vec<Reference>>{
+    let mut stmt = conn.prepare(
+        r#"
+        SELECT DISTINCT
+            pdgreference.id,
+            pdgreference.document_id,
+            pdgreference.publication_name,
+            pdgreference.publication_year,
+            pdgreference.doi,
+            pdgreference.inspire_id,
+            pdgreference.title
+        FROM
+            pdgreference
+        INNER JOIN
+            pdgmeasurement
+        ON
+            pdgmeasurement.pdgreference_id = pdgreference.id
+        WHERE
+            pdgmeasurement.pdgid = ?1
+        OR
+            (?2 AND pdgmeasurement.pdgid GLOB ?1 || '[^0-9]*')
+        ORDER BY
+            pdgreference.publication_year DESC, pdgreference.document_id
+        "#,
+    )?;
+    let references = stmt.query_map(rusqlite::params![node_id, whole_particle], |row| {
+        Ok(Reference{
+            id: row.get("id")?,
+            document_id: row.get("document_id")?,
+            publication_name: row.get("publication_name")?,
+            year: row.get("publication_year")?,
+            doi: row.get("doi")?,
+            inspire_id: row.get("inspire_id")?,
+            title: row.get("title")?,
+        })
+    })?.collect::<Result<Vec<Reference>>>()?;
+    Ok(references)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_connection() -> rusqlite::Connection{
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE pdginfo (id INTEGER, name TEXT, value TEXT);
+             CREATE TABLE pdgreference (id INTEGER, document_id TEXT, publication_name TEXT, publication_year INTEGER, doi TEXT, inspire_id TEXT, title TEXT);
+             CREATE TABLE pdgmeasurement (id INTEGER, pdgid TEXT, pdgreference_id INTEGER, technique TEXT, comment TEXT);
+             INSERT INTO pdginfo VALUES (1, 'edition', '2024');
+             INSERT INTO pdginfo VALUES (2, 'citation', 'S. Navas et al. (Particle Data Group), Phys. Rev. D 110, 030001 (2024)');
+             INSERT INTO pdginfo VALUES (3, 'license', 'CC BY 4.0');
+             INSERT INTO pdgreference VALUES (1, 'ABLIKIM 2014D', 'PR D90 012001', 2014, '10.1103/PhysRevD.90.012001', '1286574', 'Precision measurement of the mass of the tau lepton');
+             INSERT INTO pdgreference VALUES (2, 'AUBERT 2009AK', 'PR D80 092005', 2009, NULL, '823681', NULL);
+             INSERT INTO pdgreference VALUES (3, 'AAIJ 2021A', 'JHEP 2103 075', 2021, NULL, NULL, NULL);
+             INSERT INTO pdgmeasurement VALUES (1, 'S035M', 1, 'BES3', NULL);
+             INSERT INTO pdgmeasurement VALUES (2, 'S035M', 2, 'BABR', NULL);
+             INSERT INTO pdgmeasurement VALUES (3, 'S035M', 1, 'BES3', NULL);
+             INSERT INTO pdgmeasurement VALUES (4, 'S035T', 3, 'LHCB', NULL);
+             INSERT INTO pdgmeasurement VALUES (5, 'S035.1', 2, 'BABR', NULL);
+             INSERT INTO pdgmeasurement VALUES (6, 'S035.10', 3, 'LHCB', NULL);
+             INSERT INTO pdgmeasurement VALUES (7, 'S0351M', 1, 'BES3', NULL);",
+        ).unwrap();
+        conn
+    }
+
+    #[test]
+    fn test_find_edition(){
+        let edition = find_edition(&test_connection()).unwrap();
+        assert_eq!(edition.edition.as_deref(), Some("2024"));
+        assert!(edition.citation.unwrap().starts_with("S. Navas"));
+    }
+
+    #[test]
+    fn test_find_references(){
+        let conn = test_connection();
+        let ids = |node_id: &str, whole_particle: bool| find_references(&conn, node_id, whole_particle)
+            .unwrap()
+            .iter()
+            .map(|reference| reference.id)
+            .collect::<Vec<i64>>();
+        let mass = find_references(&conn, "S035M", false).unwrap();
+        assert_eq!(mass.iter().map(|reference| reference.id).collect::<Vec<i64>>(), vec![1, 2]);
+        assert_eq!(mass[0].first_author().as_deref(), Some("Ablikim"));
+        assert_eq!(ids("S035", true), vec![3, 1, 2]);
+        assert!(ids("S003", true).is_empty());
+        // ids sharing a prefix are different values
+        assert_eq!(ids("S035.1", false), vec![2]);
+        assert_eq!(ids("S035.10", false), vec![3]);
+        assert_eq!(ids("S035", false), Vec::<i64>::new());
+    }
+}